@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use crate::{
+    config::Config,
+    error::Result,
+    history::Action,
+    paths,
+    project,
+    store::{
+        self,
+        TaskStore
+    }
+};
+
+/// Everything a command runs against: the user's settings, the task store
+/// they selected, its trash and its archive, and the project in use.
+pub struct App{
+    pub config:         Config,
+    pub store:          Box<dyn TaskStore>,
+    pub trash:          Box<dyn TaskStore>,
+    pub archive:        Box<dyn TaskStore>,
+    /// The project new tasks are added to and listings are limited to.
+    pub project:        String,
+    /// Lists, searches and reports cover every project instead.
+    pub all_projects:   bool,
+    dir:                PathBuf,
+}
+
+impl App{
+    /// Opens the data directory given by `--db`/`TERMTASKER_DB`, or the default
+    /// one, using `project` or else the current project.
+    pub fn open(db: Option<PathBuf>, project: Option<String>) -> Result<App>{
+        let dir = paths::data_dir(db)?;
+        let config = Config::load(&dir)?;
+        let store = store::open(&config, &dir)?;
+        let trash = store::open_aside(&config, &dir, "trash")?;
+        let archive = store::open_aside(&config, &dir, "archive")?;
+
+        let project = match project {
+            Some(name)  => project::parse(&name)?,
+            None        => project::current(&dir)?,
+        };
+
+        Ok(App{ config, store, trash, archive, project, all_projects: false, dir })
+    }
+
+    /// Starts a new operation on the list, the trash and the archive: the
+    /// changes from here on are undone together and checked for conflicts
+    /// against the tasks as read from here on.
+    pub fn begin_operation(&mut self, action: Action){
+        self.store.begin_operation(action);
+        self.trash.begin_operation(action);
+        self.archive.begin_operation(action);
+    }
+
+    /// Makes `name` the current project, for this and later runs.
+    pub fn switch_project(&mut self, name: &str) -> Result<()>{
+        let name = project::parse(name)?;
+        project::switch(&self.dir, &name)?;
+        self.project = name;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use std::{
+    fs::{
+        self,
+        File,
+        OpenOptions
+    },
+    io::{
+        self,
+        Write
+    },
+    path::{
+        Path,
+        PathBuf
+    },
+    process
+};
+
+/// Replaces `path` with `contents` by writing a temporary file next to it and
+/// renaming it into place, so a crash never leaves a half-written file behind.
+pub fn write(path: &Path, contents: &[u8]) -> io::Result<()>{
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(".tmp-{}", process::id()));
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let result = (|| {
+        let mut tmp = File::create(&tmp_path)?;
+        tmp.write_all(contents)?;
+        tmp.sync_all()?;
+        fs::rename(&tmp_path, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+
+    result
+}
+
+/// An advisory lock held until the value is dropped.
+///
+/// The lock is taken on a `<name>.lock` file beside the guarded file rather than
+/// on the file itself, because `write` swaps the guarded file's inode on every save.
+pub struct FileLock{
+    _file: File,
+}
+
+impl FileLock{
+    fn lock_path(path: &Path) -> PathBuf{
+        let mut lock_name = path.file_name().unwrap_or_default().to_os_string();
+        lock_name.push(".lock");
+        path.with_file_name(lock_name)
+    }
+
+    fn open(path: &Path) -> io::Result<File>{
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(Self::lock_path(path))
+    }
+
+    /// Blocks until no other process holds any lock on `path`.
+    pub fn exclusive(path: &Path) -> io::Result<FileLock>{
+        let file = Self::open(path)?;
+        file.lock()?;
+
+        Ok(FileLock{ _file: file })
+    }
+
+    /// Blocks until no other process holds an exclusive lock on `path`.
+    pub fn shared(path: &Path) -> io::Result<FileLock>{
+        let file = Self::open(path)?;
+        file.lock_shared()?;
+
+        Ok(FileLock{ _file: file })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::TempDir;
+
+    #[test]
+    fn writes_leave_no_temporary_file(){
+        let dir = TempDir::new();
+        let path = dir.path.join("tasks.json");
+        write(&path, b"first").unwrap();
+        write(&path, b"second").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        assert_eq!(fs::read_dir(&dir.path).unwrap().count(), 1);
+
+        assert!(write(&dir.path.join("missing").join("tasks.json"), b"lost").is_err());
+        assert_eq!(fs::read_dir(&dir.path).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn exclusive_locks_keep_everyone_else_out(){
+        let dir = TempDir::new();
+        let path = dir.path.join("tasks.json");
+        let other = || FileLock::open(&path).unwrap();
+
+        let shared = FileLock::shared(&path).unwrap();
+        assert!(other().try_lock_shared().is_ok());
+        assert!(other().try_lock().is_err());
+        drop(shared);
+
+        let exclusive = FileLock::exclusive(&path).unwrap();
+        assert!(other().try_lock_shared().is_err());
+        drop(exclusive);
+        assert!(other().try_lock().is_ok());
+    }
+}
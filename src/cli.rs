@@ -0,0 +1,892 @@
+use chrono::{
+    Duration,
+    Local,
+    NaiveDate,
+    Utc
+};
+use clap::{
+    ArgGroup,
+    Parser,
+    Subcommand,
+    ValueEnum
+};
+use owo_colors::OwoColorize;
+use std::{
+    collections::BTreeSet,
+    path::PathBuf
+};
+
+use crate::{
+    app::App,
+    commands::{
+        self,
+        Calibration,
+        Target
+    },
+    error::Result,
+    estimate::Estimate,
+    query::Query,
+    recur::Recurrence,
+    search::{
+        self,
+        Mode
+    },
+    task::{
+        self,
+        Key,
+        Task,
+        Field,
+        Priority,
+        Status
+    },
+    view::{
+        self,
+        Column,
+        GroupBy,
+        SortKey
+    },
+    worklog
+};
+
+/// A lightweight to-do list CLI. Runs the interactive menu when no subcommand is given.
+#[derive(Debug,Parser)]
+#[command(name = "tt", version, about)]
+pub struct Cli{
+    /// Directory holding tasks.json, tasks.db and config.json
+    #[arg(long, global = true, env = "TERMTASKER_DB", value_name = "PATH")]
+    pub db:             Option<PathBuf>,
+
+    /// Use this project instead of the current one
+    #[arg(long, global = true, env = "TERMTASKER_PROJECT")]
+    pub project:        Option<String>,
+
+    /// List, search and report on every project
+    #[arg(long, global = true)]
+    pub all_projects:   bool,
+
+    #[command(subcommand)]
+    pub command:        Option<Command>,
+}
+
+#[derive(Debug,Subcommand)]
+pub enum Command{
+    /// Add a new task (menu option 1)
+    Add{
+        /// Critical, High, Medium or Low
+        #[arg(long, value_parser = task::parse_priority)]
+        priority:       Priority,
+        #[arg(long)]
+        title:          String,
+        #[arg(long, default_value = "")]
+        description:    String,
+        /// YYYY-MM-DD, or e.g. today, tomorrow, fri, next monday, +3d, in 2 weeks, end of month
+        #[arg(long, value_parser = task::parse_due_date, allow_hyphen_values = true)]
+        due_date:       NaiveDate,
+        /// Time of day it is due, HH:MM optionally followed by a time zone, e.g. "15:00 Europe/Berlin"
+        #[arg(long)]
+        due_time:       Option<String>,
+        /// Name of a task handler, repeat for several assignees
+        #[arg(long = "assignee", required = true)]
+        assignees:      Vec<String>,
+        /// ID of the task this is a subtask of
+        #[arg(long)]
+        parent:         Option<String>,
+        /// ID of a task that has to be completed first, repeat for several
+        #[arg(long)]
+        blocked_by:     Vec<String>,
+        /// Tag, repeat for several
+        #[arg(long = "tag", value_parser = task::parse_tag)]
+        tags:           Vec<String>,
+        /// Repeat the task: daily, weekly:mon,fri, monthly:15 or after:10 (days after completion)
+        #[arg(long, value_parser = |rule: &str| rule.parse::<Recurrence>())]
+        repeat:         Option<Recurrence>,
+        /// Expected work in hours (4h, 1h30m) or story points (3pt)
+        #[arg(long, value_parser = |estimate: &str| estimate.parse::<Estimate>())]
+        estimate:       Option<Estimate>,
+    },
+    /// List tasks, optionally only those matching a filter query (menu option 2)
+    List{
+        /// Sort keys, most important first, e.g. priority,due
+        #[arg(long, value_enum, value_delimiter = ',')]
+        sort:           Vec<SortKey>,
+        /// Reverse the sort order
+        #[arg(long)]
+        reverse:        bool,
+        #[arg(long, value_enum)]
+        group:          Option<GroupBy>,
+        /// One line per task
+        #[arg(long)]
+        table:          bool,
+        /// Table columns, e.g. id,due,title (implies --table)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        columns:        Vec<Column>,
+        /// List subtasks in sort order instead of under their parents
+        #[arg(long)]
+        flat:           bool,
+        /// Include archived tasks
+        #[arg(long)]
+        archived:       bool,
+        /// Filter query, quoted as one argument, e.g. 'priority:High,Critical status:open assignee:alice'
+        #[arg(value_name = "QUERY", allow_hyphen_values = true, value_parser = Query::parse)]
+        query:          Option<Query>,
+    },
+    /// Mark a task, or every task matching --where, as completed (menu option 3)
+    #[command(group(ArgGroup::new("target").required(true).args(["id", "filter"])))]
+    Done{
+        #[arg(value_parser = task::parse_key)]
+        id:             Option<Key>,
+        /// Filter query selecting the tasks to complete
+        #[arg(long = "where", value_name = "QUERY", value_parser = Query::parse)]
+        filter:         Option<Query>,
+    },
+    /// Change the status of a task, or of every task matching --where (menu option 3)
+    #[command(group(ArgGroup::new("target").required(true).args(["id", "filter"])))]
+    Status{
+        /// NotCompleted, InProgress, Blocked, InReview, Completed, Cancelled or a custom status
+        #[arg(value_parser = task::parse_status)]
+        status:         Status,
+        #[arg(value_parser = task::parse_key)]
+        id:             Option<Key>,
+        /// Filter query selecting the tasks to change
+        #[arg(long = "where", value_name = "QUERY", value_parser = Query::parse)]
+        filter:         Option<Query>,
+    },
+    /// Move a task, or every task matching --where, to the trash (menu option 4)
+    #[command(alias = "remove")]
+    #[command(group(ArgGroup::new("target").required(true).args(["id", "filter"])))]
+    Rm{
+        #[arg(value_parser = task::parse_key)]
+        id:             Option<Key>,
+        /// UUID of the task to remove when several tasks share the ID
+        #[arg(long, requires = "id")]
+        uuid:           Option<String>,
+        /// Filter query selecting the tasks to remove
+        #[arg(long = "where", value_name = "QUERY", value_parser = Query::parse)]
+        filter:         Option<Query>,
+    },
+    /// Move a completed or cancelled task, every closed task matching --where,
+    /// or without either every closed task, to the archive
+    Archive{
+        #[arg(value_parser = task::parse_key, conflicts_with = "filter")]
+        id:             Option<Key>,
+        /// Filter query selecting the tasks to archive
+        #[arg(long = "where", value_name = "QUERY", value_parser = Query::parse)]
+        filter:         Option<Query>,
+    },
+    /// Move a task from the archive back into the list
+    Unarchive{
+        #[arg(value_parser = task::parse_key)]
+        id:             Key,
+        /// UUID of the task to bring back when several archived tasks share the ID
+        #[arg(long)]
+        uuid:           Option<String>,
+    },
+    /// List the projects with how many open tasks each has, marking the current one
+    Projects,
+    /// Make a project the current one; new tasks go there and lists only show its tasks
+    Switch{
+        project:        String,
+    },
+    /// Move a task, or every task matching --where, to another project, keeping its ID
+    #[command(group(ArgGroup::new("target").required(true).args(["id", "filter"])))]
+    Move{
+        #[arg(value_parser = task::parse_key)]
+        id:             Option<Key>,
+        /// Filter query selecting the tasks to move
+        #[arg(long = "where", value_name = "QUERY", value_parser = Query::parse)]
+        filter:         Option<Query>,
+        /// The project to move the tasks to
+        #[arg(long)]
+        to:             String,
+    },
+    /// List the tasks in the trash, most recently removed first
+    Trash,
+    /// Move a task from the trash back into the list
+    Restore{
+        #[arg(value_parser = task::parse_key)]
+        id:             Key,
+        /// UUID of the task to restore when several removed tasks share the ID
+        #[arg(long)]
+        uuid:           Option<String>,
+    },
+    /// Delete a task in the trash, or the whole trash, for good
+    #[command(group(ArgGroup::new("target").required(true).args(["id", "all"])))]
+    Purge{
+        #[arg(value_parser = task::parse_key)]
+        id:             Option<Key>,
+        /// UUID of the task to purge when several removed tasks share the ID
+        #[arg(long, requires = "id")]
+        uuid:           Option<String>,
+        /// Empty the trash
+        #[arg(long)]
+        all:            bool,
+    },
+    /// Edit fields of a task, or of every task matching --where (menu option 5)
+    #[command(group(ArgGroup::new("target").required(true).args(["id", "filter"])))]
+    Edit{
+        #[arg(value_parser = task::parse_key)]
+        id:             Option<Key>,
+        /// Filter query selecting the tasks to edit
+        #[arg(long = "where", value_name = "QUERY", value_parser = Query::parse)]
+        filter:         Option<Query>,
+        #[arg(long, conflicts_with = "filter")]
+        new_id:         Option<String>,
+        #[arg(long)]
+        priority:       Option<String>,
+        #[arg(long)]
+        title:          Option<String>,
+        #[arg(long)]
+        description:    Option<String>,
+        /// YYYY-MM-DD, or e.g. tomorrow, fri, +3d, in 2 weeks, end of month
+        #[arg(long, value_parser = task::parse_due_date, allow_hyphen_values = true)]
+        due_date:       Option<NaiveDate>,
+        /// HH:MM optionally followed by a time zone, or none to make it due any time that day
+        #[arg(long)]
+        due_time:       Option<String>,
+        #[arg(long)]
+        status:         Option<String>,
+        /// Assignees separated by commas
+        #[arg(long)]
+        assignees:      Option<String>,
+        /// ID of the new parent task, or none to make it a top-level task
+        #[arg(long)]
+        parent:         Option<String>,
+        /// IDs of the tasks that block it separated by commas, or none
+        #[arg(long)]
+        blocked_by:     Option<String>,
+        /// Tags separated by commas, replacing the current ones, or none
+        #[arg(long)]
+        tags:           Option<String>,
+        /// daily, weekly:mon,fri, monthly:15, after:10 (days after completion), or none
+        #[arg(long)]
+        repeat:         Option<String>,
+        /// Hours (4h, 1h30m), story points (3pt), or none
+        #[arg(long)]
+        estimate:       Option<String>,
+    },
+    /// Add tags to a task, or to every task matching --where
+    #[command(group(ArgGroup::new("target").required(true).args(["id", "filter"])))]
+    Tag{
+        /// Tags separated by commas
+        #[arg(value_parser = task::parse_tags)]
+        tags:           BTreeSet<String>,
+        #[arg(value_parser = task::parse_key)]
+        id:             Option<Key>,
+        /// Filter query selecting the tasks to tag
+        #[arg(long = "where", value_name = "QUERY", value_parser = Query::parse)]
+        filter:         Option<Query>,
+    },
+    /// Remove tags from a task, or from every task matching --where
+    #[command(group(ArgGroup::new("target").required(true).args(["id", "filter"])))]
+    Untag{
+        /// Tags separated by commas
+        #[arg(value_parser = task::parse_tags)]
+        tags:           BTreeSet<String>,
+        #[arg(value_parser = task::parse_key)]
+        id:             Option<Key>,
+        /// Filter query selecting the tasks to untag
+        #[arg(long = "where", value_name = "QUERY", value_parser = Query::parse)]
+        filter:         Option<Query>,
+    },
+    /// Show how many tasks use each tag, optionally only among tasks matching a filter query
+    Tags{
+        /// Filter query, quoted as one argument
+        #[arg(value_name = "QUERY", allow_hyphen_values = true, value_parser = Query::parse)]
+        query:          Option<Query>,
+    },
+    /// Mark a task as blocked by other tasks until they are completed
+    Block{
+        #[arg(value_parser = task::parse_key)]
+        id:             Key,
+        /// ID of a blocking task, repeat for several
+        #[arg(long = "by", required = true)]
+        blockers:       Vec<String>,
+    },
+    /// Stop a task from being blocked by other tasks
+    Unblock{
+        #[arg(value_parser = task::parse_key)]
+        id:             Key,
+        /// ID of a blocking task, repeat for several
+        #[arg(long = "by", required = true)]
+        blockers:       Vec<String>,
+    },
+    /// List open tasks that nothing blocks, in an order they can be done
+    Ready{
+        /// Also list blocked tasks, after the tasks blocking them
+        #[arg(long)]
+        all:            bool,
+        /// One line per task
+        #[arg(long)]
+        table:          bool,
+    },
+    /// Show everything about a task, its comment thread included (menu option 7)
+    Show{
+        #[arg(value_parser = task::parse_key)]
+        id:             Key,
+    },
+    /// Add a comment to a task's thread
+    Comment{
+        #[arg(value_parser = task::parse_key)]
+        id:             Key,
+        text:           String,
+        /// Written by someone else than $TERMTASKER_USER, the user setting or the login name
+        #[arg(long)]
+        author:         Option<String>,
+    },
+    /// Show a task's comment thread
+    Comments{
+        #[arg(value_parser = task::parse_key)]
+        id:             Key,
+    },
+    /// Show every recorded change to a task, including one that was removed
+    History{
+        #[arg(value_parser = task::parse_key)]
+        id:             Key,
+    },
+    /// Show the most recent changes to any task, newest first
+    Activity{
+        /// How many changes to show
+        #[arg(long, default_value_t = 20)]
+        limit:          usize,
+    },
+    /// Revert the most recent change, such as an add, edit or removal; repeat to go further back
+    Undo{
+        /// Only show what would be reverted
+        #[arg(long)]
+        dry_run:        bool,
+    },
+    /// Reapply the most recently undone change
+    Redo{
+        /// Only show what would be reapplied
+        #[arg(long)]
+        dry_run:        bool,
+    },
+    /// Start a timer on a task, stopping the assignee's timers on other tasks
+    Start{
+        #[arg(value_parser = task::parse_key)]
+        id:             Key,
+        /// Who is working, needed when the task has several assignees
+        #[arg(long)]
+        assignee:       Option<String>,
+    },
+    /// Stop the timers running on a task
+    Stop{
+        #[arg(value_parser = task::parse_key)]
+        id:             Key,
+        /// Only stop this assignee's timer
+        #[arg(long)]
+        assignee:       Option<String>,
+    },
+    /// Log time spent on a task without a timer
+    Log{
+        #[arg(value_parser = task::parse_key)]
+        id:             Key,
+        /// e.g. 1h30m, 45m, 1.5h or 90 (minutes)
+        #[arg(value_parser = worklog::parse_duration)]
+        duration:       Duration,
+        /// Who worked, needed when the task has several assignees
+        #[arg(long)]
+        assignee:       Option<String>,
+        /// The day the work was done, e.g. yesterday or 2026-10-12 (default today)
+        #[arg(long, value_parser = task::parse_due_date, allow_hyphen_values = true)]
+        on:             Option<NaiveDate>,
+    },
+    /// Show the time logged on a task
+    Worklog{
+        #[arg(value_parser = task::parse_key)]
+        id:             Key,
+    },
+    /// Show the time logged per task and per assignee, optionally only on tasks matching a filter query
+    Time{
+        /// Filter query, quoted as one argument
+        #[arg(value_name = "QUERY", allow_hyphen_values = true, value_parser = Query::parse)]
+        query:          Option<Query>,
+    },
+    /// Compare estimates with the time logged, per assignee and per priority, optionally only for tasks matching a filter query
+    Estimates{
+        /// Filter query, quoted as one argument
+        #[arg(value_name = "QUERY", allow_hyphen_values = true, value_parser = Query::parse)]
+        query:          Option<Query>,
+    },
+    /// Print tasks, optionally only those matching a filter query, as JSON or CSV
+    Export{
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format:         ExportFormat,
+        /// Filter query, quoted as one argument
+        #[arg(value_name = "QUERY", allow_hyphen_values = true, value_parser = Query::parse)]
+        query:          Option<Query>,
+    },
+    /// Give tasks that share an ID new, unique IDs
+    Renumber{
+        /// Renumber every task 1, 2, 3... in list order instead
+        #[arg(long)]
+        compact:        bool,
+    },
+    /// Search titles, descriptions and assignees, best match first (menu option 6)
+    Find{
+        query:          String,
+        /// Only search tasks matching this filter query
+        #[arg(long = "where", value_name = "QUERY", value_parser = Query::parse)]
+        filter:         Option<Query>,
+        /// Treat the query as a regular expression
+        #[arg(long, conflicts_with = "fuzzy")]
+        regex:          bool,
+        /// Match the query's characters in order, allowing gaps
+        #[arg(long)]
+        fuzzy:          bool,
+        /// Include archived tasks
+        #[arg(long)]
+        archived:       bool,
+    },
+    /// Search for tasks whose field matches a value (menu option 6)
+    Search{
+        #[arg(help = format!("One of: {}", Field::NAMES))]
+        field:          String,
+        value:          String,
+        /// Include archived tasks
+        #[arg(long)]
+        archived:       bool,
+    },
+}
+
+#[derive(Debug,Clone,Copy,ValueEnum)]
+pub enum ExportFormat{
+    Json,
+    Csv,
+}
+
+fn target(app: &mut App, id: Option<Key>, filter: Option<Query>) -> Result<Target>{
+    match (id, filter) {
+        (Some(id), _)           => Ok(Target::Id(commands::resolve_key(app, &id)?)),
+        (None, Some(query))     => Ok(Target::Matching(query)),
+        // clap requires one of the two
+        (None, None)            => unreachable!("clap requires an ID or --where"),
+    }
+}
+
+/// One line of the estimate report: hour estimates against the time logged on
+/// them, and the time logged per story point.
+fn calibration_row(name: &str, calibration: &Calibration, width: usize) -> String{
+    let (estimated, logged, actual) = match calibration.hours > 0.0 {
+        true    => (
+            format!("{}h", (calibration.hours * 10.0).round() / 10.0),
+            worklog::format_duration(calibration.hours_logged),
+            format!("{:.0}%", calibration.hours_logged.num_seconds() as f64 / 36.0 / calibration.hours),
+        ),
+        false   => Default::default(),
+    };
+    let (points, point_logged, per_point) = match calibration.points > 0.0 {
+        true    => (
+            format!("{}", (calibration.points * 10.0).round() / 10.0),
+            worklog::format_duration(calibration.points_logged),
+            worklog::format_duration(Duration::seconds((calibration.points_logged.num_seconds() as f64 / calibration.points) as i64)),
+        ),
+        false   => Default::default(),
+    };
+
+    format!("{:<width$}  {:>5}  {:>9}  {:>9}  {:>6}  {:>7}  {:>9}  {:>9}", name, calibration.tasks, estimated, logged, actual, points, point_logged, per_point, width = width)
+        .trim_end()
+        .to_string()
+}
+
+/// Executes a single subcommand against the task store.
+pub fn run(command: Command, app: &mut App) -> Result<()>{
+    match command {
+        Command::Add { priority, title, description, due_date, due_time, assignees, parent, blocked_by, tags, repeat, estimate } => {
+            let mut task = Task::new(priority, title, description, due_date, assignees);
+            if let Some(due_time) = due_time {
+                task.set(Field::DueTime, &due_time)?;
+            }
+            task.tags = tags.into_iter().collect();
+            task.recurrence = repeat;
+            task.estimate = estimate;
+            task.parent = commands::resolve_parent(app, parent.as_deref().unwrap_or_default())?;
+            task.blocked_by = commands::resolve_blockers(app, &blocked_by.join(","))?;
+
+            task.project = app.project.clone();
+
+            let due = task.due_date.format("%a %Y-%m-%d");
+            let project = task.project.clone();
+            let id = commands::add(app, task)?;
+            println!("Task added with ID: {}, due {}", app.config.project_key(&project, id), due);
+            Ok(())
+        }
+        Command::List { sort, reverse, group, table, columns, flat, archived, query } => {
+            let mut options = app.config.list.clone();
+            if !sort.is_empty() {
+                options.sort = sort;
+            }
+            options.reverse |= reverse;
+            options.group = group.or(options.group);
+            options.table |= table || !columns.is_empty();
+            if !columns.is_empty() {
+                options.columns = columns;
+            }
+            options.tree &= !flat;
+
+            commands::list(app, &query.unwrap_or_else(Query::all), &options, archived)
+        }
+        Command::Done { id, filter } => {
+            let target = target(app, id, filter)?;
+            let change = commands::change_status(app, &target, Status::Completed)?;
+            commands::print_status_change(app, &change);
+            Ok(())
+        }
+        Command::Status { status, id, filter } => {
+            let target = target(app, id, filter)?;
+            let change = commands::change_status(app, &target, status)?;
+            commands::print_status_change(app, &change);
+            Ok(())
+        }
+        Command::Rm { id, uuid, filter } => {
+            let removed = match id {
+                Some(id)    => {
+                    let id = commands::resolve_key(app, &id)?;
+                    commands::remove(app, id, uuid.as_deref())?
+                }
+                None        => commands::remove_matching(app, &filter.unwrap_or_else(Query::all))?,
+            };
+            println!("Moved {} tasks to the trash", removed);
+            Ok(())
+        }
+        Command::Archive { id, filter } => {
+            let target = match id.is_some() || filter.is_some() {
+                true    => Some(target(app, id, filter)?),
+                false   => None,
+            };
+            let archived = commands::archive(app, target.as_ref())?;
+            println!("Archived {} tasks", archived);
+            Ok(())
+        }
+        Command::Unarchive { id, uuid } => {
+            let id = commands::resolve_key(app, &id)?;
+            let task = commands::unarchive(app, id, uuid.as_deref())?;
+            match task.id == id {
+                true    => println!("Moved task {} out of the archive", app.config.key(&task)),
+                false   => println!("Moved task {} out of the archive as task {}, another task has its old ID", app.config.project_key(&task.project, id), app.config.key(&task)),
+            }
+            Ok(())
+        }
+        Command::Projects => {
+            for project in commands::projects(app)? {
+                let marker = if project.name == app.project { "*" } else { " " };
+                println!("{} {}  {} open, {} total", marker, project.name.bold(), project.open, project.total);
+            }
+            Ok(())
+        }
+        Command::Switch { project } => {
+            app.switch_project(&project)?;
+            println!("Switched to project {}", app.project);
+            Ok(())
+        }
+        Command::Move { id, filter, to } => {
+            let target = target(app, id, filter)?;
+            let moved = commands::move_to_project(app, &target, &to)?;
+            println!("Moved {} tasks to project {}", moved, to.trim());
+            Ok(())
+        }
+        Command::Trash => {
+            let tasks = commands::trash(app)?;
+            view::print_trash(&app.config, &tasks);
+            Ok(())
+        }
+        Command::Restore { id, uuid } => {
+            let id = commands::resolve_key(app, &id)?;
+            let task = commands::restore(app, id, uuid.as_deref())?;
+            match task.id == id {
+                true    => println!("Restored task {}", app.config.key(&task)),
+                false   => println!("Restored task {} as task {}, another task has its old ID", app.config.project_key(&task.project, id), app.config.key(&task)),
+            }
+            Ok(())
+        }
+        Command::Purge { id, uuid, all: _ } => {
+            let id = id.map(|id| commands::resolve_key(app, &id)).transpose()?;
+            let purged = commands::purge(app, id, uuid.as_deref())?;
+            println!("Purged {} tasks", purged);
+            Ok(())
+        }
+        Command::Edit { id, filter, new_id, priority, title, description, due_date, due_time, status, assignees, parent, blocked_by, tags, repeat, estimate } => {
+            let edits: Vec<(Field, String)> = [
+                (Field::Id, new_id),
+                (Field::Priority, priority),
+                (Field::Title, title),
+                (Field::Description, description),
+                (Field::DueDate, due_date.map(|date| date.to_string())),
+                (Field::DueTime, due_time),
+                (Field::Status, status),
+                (Field::Assignees, assignees),
+                (Field::Parent, parent),
+                (Field::BlockedBy, blocked_by),
+                (Field::Tags, tags),
+                (Field::Recurrence, repeat),
+                (Field::Estimate, estimate),
+            ].into_iter()
+                .filter_map(|(field, value)| value.map(|value| (field, value)))
+                .collect();
+
+            let target = target(app, id, filter)?;
+            let (edited, change) = commands::edit(app, &target, &edits)?;
+            match due_date {
+                Some(date)  => println!("Edited {} tasks, now due {}", edited, date.format("%a %Y-%m-%d")),
+                None        => println!("Edited {} tasks", edited),
+            }
+            if let Some(change) = change {
+                commands::print_status_change(app, &change);
+            }
+            Ok(())
+        }
+        Command::Block { id, blockers } => {
+            let id = commands::resolve_key(app, &id)?;
+            commands::block(app, id, &blockers, false)?;
+            println!("Task {} is blocked by {}", commands::key(app, id), blockers.join(", "));
+            Ok(())
+        }
+        Command::Unblock { id, blockers } => {
+            let id = commands::resolve_key(app, &id)?;
+            commands::block(app, id, &blockers, true)?;
+            println!("Task {} is no longer blocked by {}", commands::key(app, id), blockers.join(", "));
+            Ok(())
+        }
+        Command::Tag { tags, id, filter } => {
+            let target = target(app, id, filter)?;
+            let tagged = commands::tag(app, &target, &tags, false)?;
+            println!("Tagged {} tasks", tagged);
+            Ok(())
+        }
+        Command::Untag { tags, id, filter } => {
+            let target = target(app, id, filter)?;
+            let untagged = commands::tag(app, &target, &tags, true)?;
+            println!("Untagged {} tasks", untagged);
+            Ok(())
+        }
+        Command::Tags { query } => {
+            let counts = commands::tag_counts(app, &query.unwrap_or_else(Query::all))?;
+            if counts.is_empty() {
+                println!("No tags in use");
+            }
+            let width = counts.iter().map(|(tag, _, _)| tag.chars().count()).max().unwrap_or(0);
+            for (tag, open, total) in counts {
+                let padding = " ".repeat(width - tag.chars().count());
+                println!("{}{}  {} tasks, {} open", view::colored_tag(&app.config, &tag), padding, total, open);
+            }
+            Ok(())
+        }
+        Command::Ready { all, table } => {
+            let mut options = app.config.list.clone();
+            options.table |= table;
+            commands::ready(app, all, &options)
+        }
+        Command::Show { id } => {
+            let id = commands::resolve_key(app, &id)?;
+            commands::show(app, id)
+        }
+        Command::Comment { id, text, author } => {
+            let id = commands::resolve_key(app, &id)?;
+            let author = commands::comment(app, id, &text, author.as_deref())?;
+            println!("{} commented on task {}", author, commands::key(app, id));
+            Ok(())
+        }
+        Command::Comments { id } => {
+            let id = commands::resolve_key(app, &id)?;
+            view::print_comments(&commands::find(app, id)?);
+            Ok(())
+        }
+        Command::History { id } => {
+            let id = commands::resolve_key(app, &id)?;
+            let entries = commands::history(app, id)?;
+            view::print_history(&app.config, &entries, false);
+            Ok(())
+        }
+        Command::Activity { limit } => {
+            let entries = commands::activity(app, limit)?;
+            view::print_history(&app.config, &entries, true);
+            Ok(())
+        }
+        Command::Undo { dry_run } => {
+            let operation = commands::undo(app, !dry_run)?;
+            view::print_operation(&app.config, &operation, if dry_run { "Would undo" } else { "Undid" });
+            Ok(())
+        }
+        Command::Redo { dry_run } => {
+            let operation = commands::redo(app, !dry_run)?;
+            view::print_operation(&app.config, &operation, if dry_run { "Would redo" } else { "Redid" });
+            Ok(())
+        }
+        Command::Start { id, assignee } => {
+            let id = commands::resolve_key(app, &id)?;
+            let (assignee, stopped) = commands::start_timer(app, id, assignee.as_deref())?;
+            for (other, time) in stopped {
+                println!("Stopped {}'s timer on task {} after {}", assignee, commands::key(app, other), worklog::format_duration(time));
+            }
+            println!("Started {}'s timer on task {}", assignee, commands::key(app, id));
+            Ok(())
+        }
+        Command::Stop { id, assignee } => {
+            let id = commands::resolve_key(app, &id)?;
+            let stopped = commands::stop_timer(app, id, assignee.as_deref())?;
+            let key = commands::key(app, id);
+            for (assignee, time) in stopped {
+                println!("Stopped {}'s timer on task {} after {}", assignee, key, worklog::format_duration(time));
+            }
+            Ok(())
+        }
+        Command::Log { id, duration, assignee, on } => {
+            let id = commands::resolve_key(app, &id)?;
+            let assignee = commands::log_work(app, id, assignee.as_deref(), duration, on)?;
+            println!("Logged {} for {} on task {}", worklog::format_duration(duration), assignee, commands::key(app, id));
+            Ok(())
+        }
+        Command::Worklog { id } => {
+            let id = commands::resolve_key(app, &id)?;
+            let task = commands::find(app, id)?;
+            if task.worklog.is_empty() {
+                println!("No time logged on task {}", app.config.key(&task));
+                return Ok(());
+            }
+
+            let now = Utc::now();
+            let width = task.worklog.iter().map(|work| work.assignee.chars().count()).max().unwrap_or(0);
+            let mut entries: Vec<_> = task.worklog.iter().collect();
+            entries.sort_by_key(|work| work.start);
+            for work in entries {
+                let end = match work.end {
+                    Some(end)   => end.with_timezone(&Local).format("%H:%M").to_string(),
+                    None        => "now".to_string(),
+                };
+                let marker = if work.is_running() { " ▶" } else { "" };
+                println!("{}-{:<5}  {:<width$}  {}{}", work.start.with_timezone(&Local).format("%Y-%m-%d %H:%M"), end, work.assignee, worklog::format_duration(work.duration(now)), marker, width = width);
+            }
+            println!();
+            for (assignee, time) in worklog::by_assignee(&task.worklog, now) {
+                println!("{:<width$}  {}", assignee, worklog::format_duration(time), width = width);
+            }
+            println!("{:<width$}  {}", "Total", worklog::format_duration(worklog::total(&task.worklog, now)), width = width);
+            Ok(())
+        }
+        Command::Time { query } => {
+            let totals = commands::time_totals(app, &query.unwrap_or_else(Query::all))?;
+            if totals.tasks.is_empty() {
+                println!("No time logged");
+                return Ok(());
+            }
+
+            println!("{}", "Per task".bold().underline());
+            for (task, time) in &totals.tasks {
+                let marker = if task.running_timers().is_empty() { "" } else { " ▶" };
+                println!("{:>9}  {} {}{}", worklog::format_duration(*time), app.config.key(task), task.title, marker);
+            }
+            println!();
+            println!("{}", "Per assignee".bold().underline());
+            for (assignee, time) in &totals.assignees {
+                println!("{:>9}  {}", worklog::format_duration(*time), assignee);
+            }
+            println!("{:>9}  Total", worklog::format_duration(totals.tasks.iter().map(|(_, time)| *time).sum()));
+            Ok(())
+        }
+        Command::Estimates { query } => {
+            let report = commands::estimate_report(app, &query.unwrap_or_else(Query::all))?;
+            if report.priorities.is_empty() {
+                println!("No estimated tasks");
+                return Ok(());
+            }
+
+            let names = report.assignees.keys().map(|name| name.chars().count()).chain([8]);
+            let width = names.max().unwrap_or(0);
+            let header = format!("{:<width$}  {:>5}  {:>9}  {:>9}  {:>6}  {:>7}  {:>9}  {:>9}", "", "Tasks", "Estimated", "Logged", "Actual", "Points", "Logged", "Per Point", width = width);
+
+            println!("{}", "Per assignee".bold().underline());
+            println!("{}", header.bold());
+            for (assignee, calibration) in &report.assignees {
+                println!("{}", calibration_row(assignee, calibration, width));
+            }
+            println!();
+            println!("{}", "Per priority".bold().underline());
+            println!("{}", header.bold());
+            for (priority, calibration) in &report.priorities {
+                println!("{}", calibration_row(&priority.to_string(), calibration, width));
+            }
+            Ok(())
+        }
+        Command::Export { format, query } => {
+            let query = query.unwrap_or_else(Query::all);
+            let output = match format {
+                ExportFormat::Json  => commands::export_json(app, &query)?,
+                ExportFormat::Csv   => commands::export_csv(app, &query)?,
+            };
+            print!("{}", output);
+            Ok(())
+        }
+        Command::Renumber { compact } => {
+            let changes = commands::renumber(app, compact)?;
+            if changes.is_empty() {
+                println!("All task IDs are already unique");
+            }
+            for (old_id, task) in changes {
+                println!("{} -> {} ({})", app.config.project_key(&task.project, old_id), app.config.key(&task), task.uuid);
+            }
+            Ok(())
+        }
+        Command::Find { query, filter, regex, fuzzy, archived } => {
+            let mode = match (regex, fuzzy) {
+                (true, _)   => Mode::Regex,
+                (_, true)   => Mode::Fuzzy,
+                _           => Mode::Substring,
+            };
+            let hits = commands::find_text(app, &query, mode, &filter.unwrap_or_else(Query::all), archived)?;
+            search::print_hits(&app.config, &query, &hits);
+            Ok(())
+        }
+        Command::Search { field, value, archived } => {
+            let field: Field = field.parse()?;
+            let matching_tasks = commands::search(app, field, &value, archived)?;
+            commands::print_search_results(app, field, &value, matching_tasks)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn parse(args: &[&str]) -> Command{
+        Cli::try_parse_from(["tt"].iter().chain(args)).unwrap().command.unwrap()
+    }
+
+    #[test]
+    fn options_after_a_query_are_options(){
+        let Command::List { table, columns, query, .. } = parse(&["list", "priority:Critical", "--table", "--columns", "id"]) else {
+            panic!("expected list");
+        };
+        assert!(table);
+        assert_eq!(columns.len(), 1);
+        assert!(query.is_some());
+
+        let Command::Export { format, query } = parse(&["export", "status:closed", "--format", "csv"]) else {
+            panic!("expected export");
+        };
+        assert!(matches!(format, ExportFormat::Csv));
+        assert!(query.is_some());
+    }
+
+    #[test]
+    fn queries_may_start_with_a_negation(){
+        assert!(matches!(parse(&["list", "-tag:blocked", "--table"]), Command::List { table: true, query: Some(_), .. }));
+        assert!(matches!(parse(&["tags", "-status:done"]), Command::Tags { query: Some(_) }));
+    }
+
+    #[test]
+    fn unquoted_queries_are_rejected(){
+        assert!(Cli::try_parse_from(["tt", "list", "priority:High", "status:open"]).is_err());
+        assert!(Cli::try_parse_from(["tt", "list", "priority:"]).is_err());
+    }
+
+    #[test]
+    fn ids_keep_their_prefix(){
+        let Command::Done { id, .. } = parse(&["done", "WEB-2"]) else {
+            panic!("expected done");
+        };
+        assert_eq!(id, Some(Key{ prefix: Some("WEB".to_string()), id: 2 }));
+        assert!(Cli::try_parse_from(["tt", "done", "0"]).is_err());
+    }
+}
@@ -0,0 +1,1510 @@
+
+use chrono::{
+    Duration,
+    Local,
+    NaiveDate,
+    NaiveTime,
+    Utc
+};
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+    HashMap,
+    HashSet
+};
+use uuid::Uuid;
+
+use crate::{
+    app::App,
+    config::{
+        BlockedRule,
+        SubtaskRule
+    },
+    deps,
+    due,
+    error::{
+        Error,
+        Result
+    },
+    estimate::{
+        self,
+        Estimate
+    },
+    history::{
+        self,
+        Action,
+        Entry,
+        Operation
+    },
+    project,
+    query::Query,
+    recur,
+    search::{
+        self,
+        Hit,
+        Mode
+    },
+    store::TaskStore,
+    task::{
+        self,
+        Comment,
+        Key,
+        Task,
+        Field,
+        Priority,
+        Status
+    },
+    tree,
+    view::{
+        self,
+        Column,
+        ListOptions,
+        SortKey
+    },
+    worklog::{
+        self,
+        Work
+    }
+};
+
+/// The tasks a command acts on: a single task by ID, or every task matching a query.
+pub enum Target{
+    Id(i32),
+    Matching(Query),
+}
+
+/// Returns the first task with the given ID.
+pub fn find(app: &mut App, id: i32) -> Result<Task>{
+    app.store.query(&|t| t.id == id)?
+        .into_iter()
+        .next()
+        .ok_or(Error::NotFound(id))
+}
+
+/// Refuses an ID another task in the store already uses, so it can be asked
+/// for again before anything is saved.
+pub fn check_id_free(app: &mut App, task: &Task) -> Result<()>{
+    match app.store.query(&|t| t.id == task.id && t.uuid != task.uuid)?.is_empty() {
+        true    => Ok(()),
+        false   => Err(Error::DuplicateId(task.id)),
+    }
+}
+
+/// The ID `key` refers to. A prefix, if given, has to be the one of the project
+/// of a task with that ID, wherever the task is kept; an ID no task has is left
+/// for the command to report.
+pub fn resolve_key(app: &mut App, key: &Key) -> Result<i32>{
+    let Some(prefix) = &key.prefix else {
+        return Ok(key.id);
+    };
+
+    let given = format!("{}-{}", prefix, key.id);
+    let mut tasks = app.store.query(&|t| t.id == key.id)?;
+    tasks.extend(app.trash.query(&|t| t.id == key.id)?);
+    tasks.extend(app.archive.query(&|t| t.id == key.id)?);
+    let keys: Vec<String> = tasks.iter().map(|t| app.config.key(t)).collect();
+    match keys.is_empty() || keys.iter().any(|key| key.eq_ignore_ascii_case(&given)) {
+        true    => Ok(key.id),
+        false   => Err(Error::Invalid(format!("No task {}, ID: {} belongs to {}", given, key.id, keys.join(", ")))),
+    }
+}
+
+/// The ID of the task with the given ID as it is shown, with the prefix of its
+/// project, or of the current project if there is no such task.
+pub fn key(app: &mut App, id: i32) -> String{
+    match find(app, id) {
+        Ok(task)    => app.config.key(&task),
+        Err(_)      => app.config.project_key(&app.project, id),
+    }
+}
+
+/// The next free ID: one past the highest ID in use in the list, the trash or
+/// the archive, so a new task never takes the ID of one that may come back.
+fn next_id(app: &mut App) -> Result<i32>{
+    let mut highest = 0;
+    for store in [app.store.as_mut(), app.trash.as_mut(), app.archive.as_mut()] {
+        highest = store.load()?.iter().map(|t| t.id).fold(highest, i32::max);
+    }
+
+    Ok(highest + 1)
+}
+
+/// Returns the tasks `target` refers to. An ID that matches nothing is an
+/// error, a query that matches nothing is not.
+pub fn select(app: &mut App, target: &Target) -> Result<Vec<Task>>{
+    match target {
+        Target::Id(id)          => Ok(vec![find(app, *id)?]),
+        Target::Matching(query) => matching(app, false, query, &|_| true),
+    }
+}
+
+/// Adds the task under the next free ID and returns that ID.
+pub fn add(app: &mut App, mut task: Task) -> Result<i32>{
+    loop {
+        task.id = next_id(app)?;
+
+        match app.store.insert(&task) {
+            // Another process took the ID since the tasks were loaded, try the next one.
+            Err(Error::DuplicateId(_))  => continue,
+            Err(err)                    => return Err(err),
+            Ok(())                      => return Ok(task.id),
+        }
+    }
+}
+
+/// Resolves a task given by ID or UUID to its UUID.
+fn resolve(app: &mut App, value: &str) -> Result<Uuid>{
+    if let Ok(uuid) = Uuid::parse_str(value.trim()) {
+        return match app.store.query(&|t| t.uuid == uuid)?.is_empty() {
+            true    => Err(Error::Invalid(format!("Task with UUID: {} not found", uuid))),
+            false   => Ok(uuid),
+        };
+    }
+
+    let id = resolve_key(app, &task::parse_key(value)?)?;
+    Ok(find(app, id)?.uuid)
+}
+
+/// Resolves the parent given as a task ID, a UUID or `none` for a top-level task.
+pub fn resolve_parent(app: &mut App, value: &str) -> Result<Option<Uuid>>{
+    match value.trim() {
+        "" | "none" => Ok(None),
+        parent      => Ok(Some(resolve(app, parent)?)),
+    }
+}
+
+/// Resolves comma separated blocker task IDs or UUIDs, or `none`.
+pub fn resolve_blockers(app: &mut App, value: &str) -> Result<Vec<Uuid>>{
+    value.split(',')
+        .map(str::trim)
+        .filter(|blocker| !blocker.is_empty() && *blocker != "none")
+        .map(|blocker| resolve(app, blocker))
+        .collect()
+}
+
+pub fn list(app: &mut App, query: &Query, options: &ListOptions, archived: bool) -> Result<()>{
+    let all = scope(app, archived, &|_| true)?;
+    let tasks = all.iter().filter(|t| query.matches(&app.config, t)).cloned().collect();
+    view::print_list(&app.config, &all, tasks, options);
+
+    Ok(())
+}
+
+/// Applies a subtask rule to `tasks`, the tasks about to be completed or
+/// removed: `Cascade` adds their subtasks that `affected` selects, `Refuse`
+/// fails if there are any that are not among `tasks` already. `kind` and
+/// `advice` make up the refusal message.
+fn with_subtasks(app: &mut App, mut tasks: Vec<Task>, rule: SubtaskRule, affected: impl Fn(&Task) -> bool, kind: &str, advice: &str) -> Result<Vec<Task>>{
+    if rule == SubtaskRule::Keep {
+        return Ok(tasks);
+    }
+
+    let all = app.store.load()?;
+    for index in 0..tasks.len() {
+        let subtasks: Vec<&Task> = tree::descendants(&all, tasks[index].uuid)
+            .into_iter()
+            .filter_map(|uuid| all.iter().find(|t| t.uuid == uuid))
+            .filter(|t| affected(t) && !tasks.iter().any(|selected| selected.uuid == t.uuid))
+            .collect();
+
+        if subtasks.is_empty() {
+            continue;
+        }
+        if rule == SubtaskRule::Refuse {
+            let keys: Vec<String> = subtasks.iter().map(|t| app.config.key(t)).collect();
+            return Err(Error::Invalid(format!("Task {} has {} {} ({}), {}", app.config.key(&tasks[index]), keys.len(), kind, keys.join(", "), advice)));
+        }
+        tasks.extend(subtasks.into_iter().cloned());
+    }
+
+    Ok(tasks)
+}
+
+/// The outcome of `change_status`.
+pub struct StatusChange{
+    pub status:     Status,
+    /// How many tasks changed, including subtasks closed with them.
+    pub changed:    usize,
+    /// The next occurrences added for closed recurring tasks.
+    pub next:       Vec<Task>,
+}
+
+/// Moves the targeted tasks to `status`, as far as the configured transitions
+/// allow. Closing tasks applies `subtasks.on_complete` to their open subtasks,
+/// completing them applies `dependencies.on_complete` to their open blockers,
+/// and closing a recurring task adds its next occurrence.
+pub fn change_status(app: &mut App, target: &Target, status: Status) -> Result<StatusChange>{
+    let selected = select(app, target)?;
+    set_status(app, selected, status)
+}
+
+/// Moves `selected`, tasks that may carry other unsaved changes, to `status`
+/// by the rules of `change_status` and saves them.
+fn set_status(app: &mut App, selected: Vec<Task>, status: Status) -> Result<StatusChange>{
+    let status = app.config.statuses.resolve(status)?;
+
+    let mut tasks = match status.is_open() {
+        true    => selected,
+        false   => with_subtasks(app, selected, app.config.subtasks.on_complete, |t| t.status.is_open(), "open subtasks",
+            "close them first or set subtasks.on_complete to keep or cascade in config.json")?,
+    };
+    for task in &tasks {
+        app.config.statuses.check(&task.status, &status)
+            .map_err(|err| Error::Invalid(format!("Task {}: {}", app.config.key(task), err)))?;
+    }
+    if status == Status::Completed {
+        check_blockers(app, &tasks)?;
+    }
+
+    let now = Utc::now();
+    let today = Local::now().date_naive();
+    let mut next = Vec::new();
+    for task in &mut tasks {
+        if task.status.is_open() && !status.is_open() {
+            next.extend(task.next_occurrence(today));
+        }
+        // Nobody keeps working on a closed task.
+        if !status.is_open() {
+            for work in task.worklog.iter_mut().filter(|work| work.is_running()) {
+                work.end = Some(now);
+            }
+        }
+        task.set_status(status.clone());
+    }
+
+    app.store.update_many(&tasks)?;
+    for occurrence in &mut next {
+        occurrence.id = add(app, occurrence.clone())?;
+    }
+
+    Ok(StatusChange{ status, changed: tasks.len(), next })
+}
+
+pub fn print_status_change(app: &App, change: &StatusChange){
+    println!("Marked {} tasks as {}", change.changed, change.status);
+    for task in &change.next {
+        println!("Next occurrence of {} added with ID: {}, due {}", task.title, app.config.key(task), task.due());
+    }
+}
+
+/// Applies `dependencies.on_complete` to tasks about to be completed whose
+/// blockers are still open, not counting blockers completed along with them.
+fn check_blockers(app: &mut App, tasks: &[Task]) -> Result<()>{
+    let all = app.store.load()?;
+
+    for task in tasks {
+        let open: Vec<String> = deps::open_blockers(&all, task)
+            .into_iter()
+            .filter(|blocker| !tasks.iter().any(|t| t.uuid == blocker.uuid))
+            .map(|blocker| app.config.key(blocker))
+            .collect();
+
+        if open.is_empty() {
+            continue;
+        }
+        match app.config.dependencies.on_complete {
+            BlockedRule::Refuse => return Err(Error::Invalid(format!("Task {} is blocked by open tasks ({}), complete them first or set dependencies.on_complete to warn in config.json", app.config.key(task), open.join(", ")))),
+            BlockedRule::Warn   => eprintln!("Warning: task {} is still blocked by open tasks ({})", app.config.key(task), open.join(", ")),
+        }
+    }
+
+    Ok(())
+}
+
+/// Deletes `tasks` and, depending on `subtasks.on_remove`, their subtasks.
+/// Kept subtasks move up to the closest ancestor that is not deleted, and
+/// tasks blocked by deleted tasks are no longer blocked by them.
+fn delete(app: &mut App, tasks: Vec<Task>) -> Result<usize>{
+    let tasks = with_subtasks(app, tasks, app.config.subtasks.on_remove, |_| true, "subtasks",
+        "remove them first or set subtasks.on_remove to keep or cascade in config.json")?;
+
+    let removed: HashMap<Uuid, Option<Uuid>> = tasks.iter().map(|t| (t.uuid, t.parent)).collect();
+    let is_removed = |uuid: &Uuid| removed.contains_key(uuid);
+
+    // Kept tasks lose their links to removed tasks.
+    let mut linked: Vec<Task> = app.store.query(&|t| {
+        !is_removed(&t.uuid) && (t.parent.as_ref().is_some_and(is_removed) || t.blocked_by.iter().any(is_removed))
+    })?;
+    for task in &mut linked {
+        let mut parent = task.parent;
+        let mut steps = 0;
+        while let Some(next) = parent.and_then(|p| removed.get(&p)) {
+            parent = *next;
+            steps += 1;
+            if steps > removed.len() {
+                parent = None;
+                break;
+            }
+        }
+        task.parent = parent;
+        task.blocked_by.retain(|blocker| !is_removed(blocker));
+    }
+    app.store.update_many(&linked)?;
+
+    for task in &tasks {
+        discard(app, task)?;
+    }
+
+    Ok(tasks.len())
+}
+
+/// Moves a stored task to the trash.
+fn discard(app: &mut App, task: &Task) -> Result<()>{
+    let mut trashed = task.clone();
+    trashed.deleted_at = Some(Utc::now());
+
+    drop_copies(app, &task.uuid)?;
+    app.trash.insert(&trashed)?;
+    app.store.delete(&task.uuid)
+}
+
+/// Where tasks that are not in the list are kept.
+#[derive(Clone,Copy)]
+enum Aside{
+    Trash,
+    Archive,
+}
+
+impl Aside{
+    fn store(self, app: &mut App) -> &mut dyn TaskStore{
+        match self {
+            Aside::Trash    => app.trash.as_mut(),
+            Aside::Archive  => app.archive.as_mut(),
+        }
+    }
+}
+
+/// Drops the copies of a task kept in the trash or the archive, if there are any.
+fn drop_copies(app: &mut App, uuid: &Uuid) -> Result<()>{
+    for aside in [Aside::Trash, Aside::Archive] {
+        let store = aside.store(app);
+        if !store.query(&|t| t.uuid == *uuid)?.is_empty() {
+            store.delete(uuid)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Moves a task kept aside back into the list and returns it, with a new ID if
+/// another task took its old ID since. Links to tasks that are not
+/// in the list are dropped.
+fn bring_back(app: &mut App, aside: Aside, id: i32, uuid: Option<&str>) -> Result<Task>{
+    let mut task = pick(aside.store(app).query(&|t| t.id == id)?, id, uuid)?;
+    let all = app.store.load()?;
+    let exists = |uuid: &Uuid| all.iter().any(|t| t.uuid == *uuid);
+
+    task.parent = task.parent.filter(exists);
+    task.blocked_by.retain(exists);
+    // It goes back still marked and is unmarked after, so its history tells
+    // bringing it back apart from creating it.
+    match all.iter().any(|t| t.id == task.id) {
+        true    => task.id = add(app, task.clone())?,
+        false   => app.store.insert(&task)?,
+    }
+    task.deleted_at = None;
+    task.archived_at = None;
+    app.store.update_many(std::slice::from_ref(&task))?;
+    aside.store(app).delete(&task.uuid)?;
+
+    Ok(task)
+}
+
+/// The one of `matching_tasks` with the given ID. When several share it, `uuid`
+/// selects which one, otherwise `Error::Ambiguous` lists the candidates.
+fn pick(matching_tasks: Vec<Task>, id: i32, uuid: Option<&str>) -> Result<Task>{
+    match (matching_tasks.len(), uuid) {
+        (0, _)          => return Err(Error::NotFound(id)),
+        (1, None)       => matching_tasks.first(),
+        (_, None)       => return Err(Error::Ambiguous(id, matching_tasks.iter().map(|t| t.uuid.to_string()).collect())),
+        (_, Some(uuid)) => matching_tasks.iter().find(|t| t.uuid.to_string() == uuid),
+    }.cloned().ok_or_else(|| Error::Invalid(format!("No task with ID: {} has UUID: {}", id, uuid.unwrap_or_default())))
+}
+
+/// Moves the task with the given ID to the trash and returns how many tasks
+/// were removed, including subtasks removed with it. When several tasks share
+/// the ID, `uuid` selects which one to remove, otherwise `Error::Ambiguous`
+/// lists the candidates.
+pub fn remove(app: &mut App, id: i32, uuid: Option<&str>) -> Result<usize>{
+    let task = pick(app.store.query(&|t| t.id == id)?, id, uuid)?;
+    delete(app, vec![task])
+}
+
+/// The tasks in the trash, most recently removed first.
+pub fn trash(app: &mut App) -> Result<Vec<Task>>{
+    let mut tasks = app.trash.load()?;
+    tasks.sort_by_key(|task| std::cmp::Reverse(task.deleted_at));
+
+    Ok(tasks)
+}
+
+/// Moves a task from the trash back into the list and returns it.
+pub fn restore(app: &mut App, id: i32, uuid: Option<&str>) -> Result<Task>{
+    bring_back(app, Aside::Trash, id, uuid)
+}
+
+/// Deletes tasks from the trash for good: the one with the given ID, or with
+/// `id` None all of them. Returns how many were purged.
+pub fn purge(app: &mut App, id: Option<i32>, uuid: Option<&str>) -> Result<usize>{
+    let tasks = match id {
+        Some(id)    => vec![pick(app.trash.query(&|t| t.id == id)?, id, uuid)?],
+        None        => app.trash.load()?,
+    };
+    for task in &tasks {
+        app.trash.delete(&task.uuid)?;
+    }
+
+    Ok(tasks.len())
+}
+
+/// Purges the tasks that have been in the trash longer than `trash.retention_days`.
+pub fn expire_trash(app: &mut App) -> Result<usize>{
+    let days = app.config.trash.retention_days;
+    if days == 0 {
+        return Ok(0);
+    }
+
+    let cutoff = Utc::now() - Duration::days(days.into());
+    let expired = app.trash.query(&|t| t.deleted_at.is_some_and(|at| at < cutoff))?;
+    for task in &expired {
+        app.trash.delete(&task.uuid)?;
+    }
+
+    Ok(expired.len())
+}
+
+/// Moves closed tasks to the archive: the targeted ones, or with no target
+/// every closed task. Returns how many were archived.
+pub fn archive(app: &mut App, target: Option<&Target>) -> Result<usize>{
+    let tasks = match target {
+        Some(Target::Id(id))    => {
+            let task = find(app, *id)?;
+            if task.status.is_open() {
+                return Err(Error::Invalid(format!("Task {} is still open, only completed or cancelled tasks can be archived", app.config.key(&task))));
+            }
+            vec![task]
+        }
+        Some(target)            => select(app, target)?.into_iter().filter(|t| !t.status.is_open()).collect(),
+        None                    => scope(app, false, &|t| !t.status.is_open())?,
+    };
+
+    shelve(app, tasks)
+}
+
+/// Archives the tasks that were closed more than `archive.after_days` ago.
+pub fn auto_archive(app: &mut App) -> Result<usize>{
+    let days = app.config.archive.after_days;
+    if days == 0 {
+        return Ok(0);
+    }
+
+    let cutoff = Utc::now() - Duration::days(days.into());
+    let closed_at = |task: &Task| task.status_history.iter()
+        .rev()
+        .find(|transition| !transition.to.is_open())
+        .map(|transition| transition.at);
+    let mut tasks = app.store.query(&|t| !t.status.is_open() && closed_at(t).is_some_and(|at| at < cutoff))?;
+    if tasks.is_empty() {
+        return Ok(0);
+    }
+
+    // A task an undo brought back stays until it is changed again, otherwise
+    // undo could never get past archiving it.
+    let events = app.store.events()?;
+    let undone = |task: &Task| events.iter()
+        .rev()
+        .find(|event| event.task().is_some_and(|t| t.uuid == task.uuid))
+        .is_some_and(|event| matches!(event.action, Action::Undo(_)));
+    tasks.retain(|task| !undone(task));
+
+    shelve(app, tasks)
+}
+
+/// Moves `tasks` to the archive. They are marked archived first, so their
+/// history tells archiving apart from removal.
+fn shelve(app: &mut App, mut tasks: Vec<Task>) -> Result<usize>{
+    let now = Utc::now();
+    for task in &mut tasks {
+        task.archived_at = Some(now);
+    }
+    app.store.update_many(&tasks)?;
+
+    for task in &tasks {
+        drop_copies(app, &task.uuid)?;
+        app.archive.insert(task)?;
+        app.store.delete(&task.uuid)?;
+    }
+
+    Ok(tasks.len())
+}
+
+/// Moves a task from the archive back into the list and returns it.
+pub fn unarchive(app: &mut App, id: i32, uuid: Option<&str>) -> Result<Task>{
+    bring_back(app, Aside::Archive, id, uuid)
+}
+
+/// The tasks of the current project, or of every project, matching `filter`:
+/// those in the list and, with `archived`, those in the archive.
+fn scope(app: &mut App, archived: bool, filter: &dyn Fn(&Task) -> bool) -> Result<Vec<Task>>{
+    let project = (!app.all_projects).then(|| app.project.clone());
+    let filter = |t: &Task| project.as_ref().is_none_or(|project| t.project == *project) && filter(t);
+
+    let mut tasks = app.store.query(&filter)?;
+    if archived {
+        tasks.extend(app.archive.query(&filter)?);
+    }
+
+    Ok(tasks)
+}
+
+/// The tasks `scope` finds that also match `query`.
+fn matching(app: &mut App, archived: bool, query: &Query, filter: &dyn Fn(&Task) -> bool) -> Result<Vec<Task>>{
+    let tasks = scope(app, archived, filter)?;
+    Ok(tasks.into_iter().filter(|t| query.matches(&app.config, t)).collect())
+}
+
+/// A project with how many of its tasks are open and how many there are.
+pub struct ProjectSummary{
+    pub name:   String,
+    pub open:   usize,
+    pub total:  usize,
+}
+
+/// Every project that has tasks, and the current one, by name.
+pub fn projects(app: &mut App) -> Result<Vec<ProjectSummary>>{
+    let mut counts: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    counts.entry(app.project.clone()).or_default();
+    for task in app.store.load()? {
+        let (open, total) = counts.entry(task.project).or_default();
+        *open += usize::from(task.status.is_open());
+        *total += 1;
+    }
+
+    Ok(counts.into_iter().map(|(name, (open, total))| ProjectSummary{ name, open, total }).collect())
+}
+
+/// Moves the targeted tasks to `project`, keeping their IDs and UUIDs, and
+/// returns how many there were.
+pub fn move_to_project(app: &mut App, target: &Target, project: &str) -> Result<usize>{
+    let project = project::parse(project)?;
+    let mut tasks = select(app, target)?;
+    for task in &mut tasks {
+        task.project = project.clone();
+    }
+    app.store.update_many(&tasks)?;
+
+    Ok(tasks.len())
+}
+
+/// Removes every task matching `query` and returns how many were removed.
+pub fn remove_matching(app: &mut App, query: &Query) -> Result<usize>{
+    let tasks = matching(app, false, query, &|_| true)?;
+    delete(app, tasks)
+}
+
+/// Refuses changes that would make a task a subtask of itself, make it wait
+/// on itself through its blockers, or change its status in a way the
+/// configured transitions do not allow.
+fn validate(app: &mut App, tasks: &[Task]) -> Result<()>{
+    let mut all = app.store.load()?;
+    for task in &mut all {
+        if let Some(changed) = tasks.iter().find(|t| t.uuid == task.uuid) {
+            app.config.statuses.check(&task.status, &changed.status)
+                .map_err(|err| Error::Invalid(format!("Task {}: {}", app.config.key(task), err)))?;
+            task.parent = changed.parent;
+            task.blocked_by = changed.blocked_by.clone();
+        }
+    }
+
+    if let Some(task) = tree::find_cycle(&all) {
+        return Err(Error::Invalid(format!("Task {} cannot be a subtask of itself or of one of its subtasks", app.config.key(task))));
+    }
+    if let Some(task) = deps::find_cycle(&all) {
+        return Err(Error::Invalid(format!("Task {} cannot be blocked by itself or by a task it blocks", app.config.key(task))));
+    }
+
+    Ok(())
+}
+
+/// Adds (or with `unblock`, removes) `blockers` to the tasks blocking task `id`.
+pub fn block(app: &mut App, id: i32, blockers: &[String], unblock: bool) -> Result<()>{
+    let mut task = find(app, id)?;
+
+    for blocker in blockers {
+        let blocker = resolve(app, blocker)?;
+        if unblock {
+            task.blocked_by.retain(|uuid| *uuid != blocker);
+        } else if blocker == task.uuid {
+            return Err(Error::Invalid("A task cannot block itself".to_string()));
+        } else if !task.blocked_by.contains(&blocker) {
+            task.blocked_by.push(blocker);
+        }
+    }
+
+    update(app, &task)
+}
+
+/// Lists open tasks in an order they can be worked through, most urgent first
+/// where the dependencies allow. Only tasks without open blockers are listed
+/// unless `all` is set.
+pub fn ready(app: &mut App, all: bool, options: &ListOptions) -> Result<()>{
+    let tasks = app.store.load()?;
+
+    let mut open: Vec<Task> = scope(app, false, &|t| t.status.is_open())?;
+    view::sort(&mut open, &[SortKey::Priority, SortKey::Due], false);
+    let ordered: Vec<Task> = deps::order(open)
+        .into_iter()
+        .filter(|task| all || deps::open_blockers(&tasks, task).is_empty())
+        .collect();
+
+    // The order is the point of this list, so it is neither sorted, nested nor grouped.
+    let mut options = ListOptions{ sort: Vec::new(), reverse: false, group: None, tree: false, ..options.clone() };
+    if all && !options.columns.contains(&Column::BlockedBy) {
+        options.columns.push(Column::BlockedBy);
+    }
+    view::print_list(&app.config, &tasks, ordered, &options);
+
+    Ok(())
+}
+
+/// Saves a task that was fetched with `find` and changed in memory.
+pub fn update(app: &mut App, task: &Task) -> Result<()>{
+    validate(app, std::slice::from_ref(task))?;
+    app.store.update(task)
+}
+
+/// Applies every `(field, value)` edit to the targeted tasks and saves them
+/// together, returning how many were edited and, if the status was edited,
+/// the status change. Nothing is saved if any value is invalid.
+pub fn edit(app: &mut App, target: &Target, edits: &[(Field, String)]) -> Result<(usize, Option<StatusChange>)>{
+    let mut tasks = select(app, target)?;
+
+    // Parents and blockers are given by ID, which only the store can turn into
+    // a UUID, and custom statuses are only known to the configuration.
+    let mut resolved = Vec::with_capacity(edits.len());
+    let mut status = None;
+    for (field, value) in edits {
+        let value = match field {
+            Field::Parent       => resolve_parent(app, value)?.map(|uuid| uuid.to_string()).unwrap_or_default(),
+            Field::BlockedBy    => resolve_blockers(app, value)?.iter().map(Uuid::to_string).collect::<Vec<_>>().join(","),
+            Field::Status       => {
+                status = Some(app.config.statuses.parse(value)?);
+                continue;
+            }
+            _                   => value.clone(),
+        };
+        resolved.push((*field, value));
+    }
+
+    for task in &mut tasks {
+        for (field, value) in &resolved {
+            task.set(*field, value)?;
+        }
+    }
+
+    validate(app, &tasks)?;
+    let edited = tasks.len();
+    // A new status is subject to the same rules as `change_status`, and is
+    // saved together with the other edits.
+    match status {
+        Some(status)    => Ok((edited, Some(set_status(app, tasks, status)?))),
+        None            => {
+            app.store.update_many(&tasks)?;
+            Ok((edited, None))
+        }
+    }
+}
+
+pub fn search(app: &mut App, field: Field, value: &str, archived: bool) -> Result<Vec<Task>>{
+    match field {
+        Field::Id           => {
+            let id = resolve_key(app, &task::parse_key(value)?)?;
+            scope(app, archived, &|t| t.id == id)
+        }
+        Field::Priority     => {
+            let priority = task::parse_priority(value)?;
+            scope(app, archived, &|t| t.priority == priority)
+        }
+        Field::Title        => {
+            let value = value.trim().to_lowercase();
+            scope(app, archived, &|t| t.title.to_lowercase().contains(&value))
+        }
+        Field::Description  => {
+            let value = value.trim().to_lowercase();
+            scope(app, archived, &|t| t.description.to_lowercase().contains(&value))
+        }
+        Field::DueDate      => {
+            let due_date = task::parse_due_date(value)?;
+            scope(app, archived, &|t| t.due_date == due_date)
+        }
+        Field::DueTime      => match value.trim() {
+            "" | "none" => scope(app, archived, &|t| t.due_time.is_none()),
+            time        => {
+                let (time, zone) = due::parse_time(time)?;
+                scope(app, archived, &|t| t.due_time.as_ref().is_some_and(|due| due.at.with_timezone(&zone).time() == time))
+            }
+        },
+        Field::Status       => {
+            let status = app.config.statuses.parse(value)?;
+            scope(app, archived, &|t| t.status == status)
+        }
+        Field::Assignees    => {
+            let value = value.trim().to_lowercase();
+            scope(app, archived, &|t| t.assignees.iter().any(|a| a.to_lowercase() == value))
+        }
+        Field::Parent       => {
+            let parent = resolve_parent(app, value)?;
+            scope(app, archived, &|t| t.parent == parent)
+        }
+        Field::Recurrence   => {
+            let recurrence = recur::parse(value)?;
+            scope(app, archived, &|t| t.recurrence == recurrence)
+        }
+        Field::Estimate     => {
+            let estimate = estimate::parse(value)?;
+            scope(app, archived, &|t| t.estimate == estimate)
+        }
+        Field::Tags         => {
+            let tags = task::parse_tags(value)?;
+            scope(app, archived, &|t| tags.is_subset(&t.tags) && (!tags.is_empty() || t.tags.is_empty()))
+        }
+        Field::BlockedBy    => {
+            let blockers = resolve_blockers(app, value)?;
+            scope(app, archived, &|t| blockers.iter().all(|b| t.blocked_by.contains(b)) && (!blockers.is_empty() || t.blocked_by.is_empty()))
+        }
+    }
+}
+
+/// Adds `tags` to (or with `remove`, removes them from) the targeted tasks and
+/// returns how many tasks there were.
+pub fn tag(app: &mut App, target: &Target, tags: &BTreeSet<String>, remove: bool) -> Result<usize>{
+    let mut tasks = select(app, target)?;
+    for task in &mut tasks {
+        if remove {
+            task.tags.retain(|tag| !tags.contains(tag));
+        } else {
+            task.tags.extend(tags.iter().cloned());
+        }
+    }
+
+    app.store.update_many(&tasks)?;
+    Ok(tasks.len())
+}
+
+/// Every tag used by the tasks matching `query` with `(open, total)` task
+/// counts, most used first.
+pub fn tag_counts(app: &mut App, query: &Query) -> Result<Vec<(String, usize, usize)>>{
+    let mut counts: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    for task in matching(app, false, query, &|_| true)? {
+        for tag in task.tags {
+            let (open, total) = counts.entry(tag).or_default();
+            if task.status.is_open() {
+                *open += 1;
+            }
+            *total += 1;
+        }
+    }
+
+    let mut counts: Vec<(String, usize, usize)> = counts.into_iter()
+        .map(|(tag, (open, total))| (tag, open, total))
+        .collect();
+    counts.sort_by_key(|(_, _, total)| std::cmp::Reverse(*total));
+
+    Ok(counts)
+}
+
+/// Adds a comment to the task's thread, written by `author` or the current user,
+/// and returns who wrote it.
+pub fn comment(app: &mut App, id: i32, text: &str, author: Option<&str>) -> Result<String>{
+    if text.trim().is_empty() {
+        return Err(Error::Invalid("The comment cannot be empty".to_string()));
+    }
+
+    let mut task = find(app, id)?;
+    let author = author.map(str::trim).filter(|author| !author.is_empty()).map(str::to_string).unwrap_or_else(|| app.config.user());
+    task.comments.push(Comment{ author: author.clone(), at: Utc::now(), text: text.trim().to_string() });
+    app.store.update(&task)?;
+
+    Ok(author)
+}
+
+/// Every recorded change to the task with the given ID, oldest first. A task
+/// that was deleted is found by the ID it last had.
+pub fn history(app: &mut App, id: i32) -> Result<Vec<Entry>>{
+    let events = app.store.events()?;
+    let uuid = match find(app, id) {
+        Ok(task)                => task.uuid,
+        Err(Error::NotFound(_)) => events.iter()
+            .rev()
+            .filter_map(|event| event.task())
+            .find(|task| task.id == id)
+            .ok_or(Error::NotFound(id))?
+            .uuid,
+        Err(err)                => return Err(err),
+    };
+
+    Ok(events.iter()
+        .filter(|event| event.task().is_some_and(|task| task.uuid == uuid))
+        .flat_map(|event| event.entries())
+        .collect())
+}
+
+/// The `limit` most recent changes to any task, newest first.
+pub fn activity(app: &mut App, limit: usize) -> Result<Vec<Entry>>{
+    Ok(app.store.events()?
+        .iter()
+        .rev()
+        .flat_map(|event| event.entries().into_iter().rev())
+        .take(limit)
+        .collect())
+}
+
+/// Reverts the most recent change that has not been undone yet, or with
+/// `apply` false only returns it so it can be previewed.
+pub fn undo(app: &mut App, apply: bool) -> Result<Operation>{
+    let (mut undo, _) = history::stacks(app.store.events()?);
+    let operation = undo.pop().ok_or_else(|| Error::Invalid("Nothing to undo".to_string()))?;
+
+    if apply {
+        let changes = operation.tasks().into_iter().map(|(before, after)| (after, before)).collect();
+        app.begin_operation(Action::Undo(operation.id));
+        reset(app, changes, &operation.unarchived(), "undone")?;
+    }
+    Ok(operation)
+}
+
+/// Reapplies the most recently undone change, or with `apply` false only
+/// returns it so it can be previewed.
+pub fn redo(app: &mut App, apply: bool) -> Result<Operation>{
+    let (_, mut redo) = history::stacks(app.store.events()?);
+    let operation = redo.pop().ok_or_else(|| Error::Invalid("Nothing to redo".to_string()))?;
+
+    if apply {
+        let changes = operation.tasks();
+        app.begin_operation(Action::Redo(operation.id));
+        reset(app, changes, &operation.archived(), "redone")?;
+    }
+    Ok(operation)
+}
+
+/// Puts each task from its `current` state back into its `target` one, given
+/// as `(current, target)`. Nothing is changed if any task has been changed
+/// since, by a change that cannot be undone first. Tasks that are taken out
+/// go to the archive if listed in `archived` and to the trash otherwise, and
+/// tasks put back are taken out of them.
+fn reset(app: &mut App, changes: Vec<(Option<Task>, Option<Task>)>, archived: &[Uuid], verb: &str) -> Result<()>{
+    let all = app.store.load()?;
+    let same = |a: Option<&Task>, b: Option<&Task>| serde_json::to_value(a).ok() == serde_json::to_value(b).ok();
+
+    for (current, target) in &changes {
+        let Some(task) = current.as_ref().or(target.as_ref()) else {
+            continue;
+        };
+        let stored = all.iter().find(|stored| stored.uuid == task.uuid);
+        if !same(stored, current.as_ref()) {
+            return Err(Error::Invalid(format!("Task {} was changed since, so this change cannot be {}", app.config.key(task), verb)));
+        }
+    }
+
+    // Every ID is checked before anything moves, so a task that took one of
+    // them since cannot leave the change half done.
+    let changed = |uuid: &Uuid| changes.iter().any(|(current, target)| current.as_ref().or(target.as_ref()).is_some_and(|t| t.uuid == *uuid));
+    let mut after: Vec<&Task> = all.iter().filter(|t| !changed(&t.uuid)).collect();
+    after.extend(changes.iter().filter_map(|(_, target)| target.as_ref()));
+    for (current, target) in &changes {
+        let Some(target) = target.as_ref().filter(|target| current.as_ref().is_none_or(|current| current.id != target.id)) else {
+            continue;
+        };
+        if let Some(other) = after.iter().find(|t| t.id == target.id && t.uuid != target.uuid) {
+            return Err(Error::Invalid(format!("Task {} cannot get its ID back, task {} took it since, so this change cannot be {}", app.config.key(target), app.config.key(other), verb)));
+        }
+    }
+
+    // Deletions first, so restored tasks can take back the IDs they free.
+    let (archive, trash): (Vec<Task>, Vec<Task>) = changes.iter()
+        .filter(|(_, target)| target.is_none())
+        .filter_map(|(current, _)| current.clone())
+        .partition(|task| archived.contains(&task.uuid));
+    for task in &trash {
+        discard(app, task)?;
+    }
+    shelve(app, archive)?;
+    let updates: Vec<Task> = changes.iter()
+        .filter(|(current, _)| current.is_some())
+        .filter_map(|(_, target)| target.clone())
+        .collect();
+    if !updates.is_empty() {
+        app.store.update_many(&updates)?;
+    }
+    for (_, target) in changes.iter().filter(|(current, _)| current.is_none()) {
+        if let Some(target) = target {
+            app.store.insert(target)?;
+            drop_copies(app, &target.uuid)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints everything about a task, its comment thread included.
+pub fn show(app: &mut App, id: i32) -> Result<()>{
+    let all = app.store.load()?;
+    let task = find(app, id)?;
+    view::print_task(&app.config, &all, task);
+
+    Ok(())
+}
+
+/// Who logs time on `task`: `assignee` if given, otherwise its only assignee.
+fn worker(app: &App, task: &Task, assignee: Option<&str>) -> Result<String>{
+    match (assignee.map(str::trim), task.assignees.as_slice()) {
+        (Some(""), _)           => Err(Error::Invalid("The assignee cannot be empty".to_string())),
+        (Some(assignee), _)     => Ok(assignee.to_string()),
+        (None, [assignee])      => Ok(assignee.clone()),
+        (None, _)               => Err(Error::Invalid(format!("Task {} has several assignees, choose who worked on it with --assignee", app.config.key(task)))),
+    }
+}
+
+/// Starts a timer for `assignee` on the task and returns who it runs for. The
+/// assignee's timers on other tasks are stopped and returned with their durations.
+pub fn start_timer(app: &mut App, id: i32, assignee: Option<&str>) -> Result<(String, Vec<(i32, Duration)>)>{
+    let mut task = find(app, id)?;
+    let assignee = worker(app, &task, assignee)?;
+    if task.running_timers().iter().any(|work| work.assignee == assignee) {
+        return Err(Error::Invalid(format!("A timer for {} is already running on task {}", assignee, app.config.key(&task))));
+    }
+
+    // Someone works on one task at a time.
+    let now = Utc::now();
+    let mut stopped = Vec::new();
+    let mut tasks = app.store.query(&|t| t.uuid != task.uuid && t.running_timers().iter().any(|work| work.assignee == assignee))?;
+    for other in &mut tasks {
+        for work in other.worklog.iter_mut().filter(|work| work.is_running() && work.assignee == assignee) {
+            work.end = Some(now);
+            stopped.push((other.id, work.duration(now)));
+        }
+    }
+
+    task.worklog.push(Work{ assignee: assignee.clone(), start: now, end: None });
+    tasks.push(task);
+    app.store.update_many(&tasks)?;
+
+    Ok((assignee, stopped))
+}
+
+/// Stops the timers running on the task, only `assignee`'s if given, and
+/// returns whose timers stopped after how long.
+pub fn stop_timer(app: &mut App, id: i32, assignee: Option<&str>) -> Result<Vec<(String, Duration)>>{
+    let mut task = find(app, id)?;
+    let now = Utc::now();
+
+    let mut stopped = Vec::new();
+    for work in task.worklog.iter_mut().filter(|work| work.is_running() && assignee.is_none_or(|assignee| work.assignee == assignee.trim())) {
+        work.end = Some(now);
+        stopped.push((work.assignee.clone(), work.duration(now)));
+    }
+    if stopped.is_empty() {
+        let whose = assignee.map(|assignee| format!(" for {}", assignee.trim())).unwrap_or_default();
+        return Err(Error::Invalid(format!("No timer{} is running on task {}", whose, app.config.key(&task))));
+    }
+
+    app.store.update(&task)?;
+    Ok(stopped)
+}
+
+/// Logs `duration` of work by `assignee` on the task, ending now or, for a
+/// past date, starting at the beginning of that day. Returns who it was logged for.
+pub fn log_work(app: &mut App, id: i32, assignee: Option<&str>, duration: Duration, on: Option<NaiveDate>) -> Result<String>{
+    let mut task = find(app, id)?;
+    let assignee = worker(app, &task, assignee)?;
+
+    let now = Utc::now();
+    let start = match on {
+        Some(date) if date > due::today()   => return Err(Error::Invalid("Work cannot be logged for a future date".to_string())),
+        Some(date) if date < due::today()   => due::DueTime::new(date, NaiveTime::MIN, due::local_zone()).at,
+        _                                   => now - duration,
+    };
+
+    task.worklog.push(Work{ assignee: assignee.clone(), start, end: Some(start + duration) });
+    app.store.update(&task)?;
+
+    Ok(assignee)
+}
+
+/// The outcome of `time_totals`.
+pub struct TimeTotals{
+    /// Tasks with time logged and how much, most first.
+    pub tasks:      Vec<(Task, Duration)>,
+    pub assignees:  BTreeMap<String, Duration>,
+}
+
+/// The time logged on each task matching `query` that has any, and the time
+/// each assignee logged on them.
+pub fn time_totals(app: &mut App, query: &Query) -> Result<TimeTotals>{
+    let now = Utc::now();
+    let tasks = matching(app, false, query, &|t| !t.worklog.is_empty())?;
+
+    let assignees = worklog::by_assignee(tasks.iter().flat_map(|task| &task.worklog), now);
+    let mut totals: Vec<(Task, Duration)> = tasks.into_iter()
+        .map(|task| {
+            let total = worklog::total(&task.worklog, now);
+            (task, total)
+        })
+        .collect();
+    totals.sort_by_key(|(_, total)| std::cmp::Reverse(*total));
+
+    Ok(TimeTotals{ tasks: totals, assignees })
+}
+
+/// Estimated and logged work on a set of tasks. Hour and story point
+/// estimates are kept apart, each with the time logged on the tasks estimated that way.
+#[derive(Debug,Default)]
+pub struct Calibration{
+    pub tasks:          usize,
+    pub hours:          f64,
+    pub hours_logged:   Duration,
+    pub points:         f64,
+    pub points_logged:  Duration,
+}
+
+impl Calibration{
+    fn add(&mut self, estimate: Estimate, logged: Duration){
+        self.tasks += 1;
+        match estimate {
+            Estimate::Hours(hours)      => {
+                self.hours += hours;
+                self.hours_logged += logged;
+            }
+            Estimate::Points(points)    => {
+                self.points += points;
+                self.points_logged += logged;
+            }
+        }
+    }
+}
+
+/// The outcome of `estimate_report`.
+pub struct EstimateReport{
+    pub assignees:  BTreeMap<String, Calibration>,
+    /// Most urgent first.
+    pub priorities: Vec<(Priority, Calibration)>,
+}
+
+/// Compares the estimates of the tasks matching `query` with the time logged
+/// on them. Per assignee, a task's estimate is split evenly between its
+/// assignees and compared with the time each of them logged.
+pub fn estimate_report(app: &mut App, query: &Query) -> Result<EstimateReport>{
+    let now = Utc::now();
+    let tasks = matching(app, false, query, &|t| t.estimate.is_some())?;
+
+    let mut assignees: BTreeMap<String, Calibration> = BTreeMap::new();
+    let mut priorities: Vec<(Priority, Calibration)> = Vec::new();
+    for task in &tasks {
+        let Some(estimate) = task.estimate else { continue };
+
+        let logged = worklog::by_assignee(&task.worklog, now);
+        for assignee in &task.assignees {
+            let time = logged.get(assignee).copied().unwrap_or_else(Duration::zero);
+            assignees.entry(assignee.clone()).or_default().add(estimate.share(task.assignees.len()), time);
+        }
+
+        let total = worklog::total(&task.worklog, now);
+        match priorities.iter_mut().find(|(priority, _)| *priority == task.priority) {
+            Some((_, calibration))  => calibration.add(estimate, total),
+            None                    => {
+                let mut calibration = Calibration::default();
+                calibration.add(estimate, total);
+                priorities.push((task.priority.clone(), calibration));
+            }
+        }
+    }
+    priorities.sort_by_key(|(priority, _)| std::cmp::Reverse(priority.level()));
+
+    Ok(EstimateReport{ assignees, priorities })
+}
+
+/// Full-text search over the title, description and assignees, best match first.
+pub fn find_text(app: &mut App, text: &str, mode: Mode, filter: &Query, archived: bool) -> Result<Vec<Hit>>{
+    search::search(matching(app, archived, filter, &|_| true)?, text, mode)
+}
+
+/// Prints the tasks `search` found, one line each.
+pub fn print_search_results(app: &mut App, field: Field, value: &str, matching_tasks: Vec<Task>) -> Result<()>{
+    // Echo the date a relative due date such as `tomorrow` stood for.
+    let value = match field {
+        Field::DueDate  => task::parse_due_date(value)?.format("%a %Y-%m-%d").to_string(),
+        _               => value.to_string(),
+    };
+    let relation = match field {
+        Field::Title | Field::Description   => "containing",
+        _                                   => "equal to",
+    };
+    if matching_tasks.is_empty() {
+        println!("No tasks found with {} {} {}", field, relation, value);
+        return Ok(());
+    }
+
+    println!("{} tasks found with {} {} {}", matching_tasks.len(), field, relation, value);
+    let all = app.store.load()?;
+    let options = ListOptions{ table: true, tree: false, group: None, ..app.config.list.clone() };
+    view::print_list(&app.config, &all, matching_tasks, &options);
+
+    Ok(())
+}
+
+/// Gives tasks new IDs and returns `(old ID, task)` for every task that
+/// changed. By default only tasks sharing an ID with an earlier task are moved
+/// to fresh IDs; with `compact` every task is numbered 1, 2, 3... in order.
+pub fn renumber(app: &mut App, compact: bool) -> Result<Vec<(i32, Task)>>{
+    let tasks = app.store.load()?;
+    let mut changes = Vec::new();
+    let mut next = next_id(app)?;
+    let mut seen = HashSet::new();
+
+    for (index, task) in tasks.iter().enumerate() {
+        let new_id = if compact {
+            index as i32 + 1
+        } else if !seen.insert(task.id) {
+            next += 1;
+            next - 1
+        } else {
+            task.id
+        };
+
+        if new_id != task.id {
+            let mut renumbered = task.clone();
+            renumbered.id = new_id;
+            changes.push((task.id, renumbered));
+        }
+    }
+
+    let changed: Vec<Task> = changes.iter().map(|(_, task)| task.clone()).collect();
+    app.store.update_many(&changed)?;
+
+    Ok(changes)
+}
+
+fn csv_field(value: &str) -> String{
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Renders the tasks matching `query` as CSV with one row per task.
+pub fn export_csv(app: &mut App, query: &Query) -> Result<String>{
+    let tasks = matching(app, false, query, &|_| true)?;
+
+    let mut output = String::from("id,uuid,priority,title,description,due_date,due_time,time_zone,status,assignees,parent,blocked_by,tags,recurrence,series,logged_minutes,estimate,comments,project\n");
+    for task in tasks {
+        let row = [
+            app.config.key(&task),
+            task.uuid.to_string(),
+            task.priority.to_string(),
+            task.title,
+            task.description,
+            task.due_date.to_string(),
+            task.due_time.as_ref().map(|time| time.at.to_rfc3339()).unwrap_or_default(),
+            task.due_time.as_ref().map(|time| time.zone.to_string()).unwrap_or_default(),
+            task.status.to_string(),
+            task.assignees.join(";"),
+            task.parent.map(|parent| parent.to_string()).unwrap_or_default(),
+            task.blocked_by.iter().map(Uuid::to_string).collect::<Vec<_>>().join(";"),
+            task.tags.iter().cloned().collect::<Vec<_>>().join(";"),
+            task.recurrence.map(|recurrence| recurrence.to_string()).unwrap_or_default(),
+            task.series.map(|series| series.to_string()).unwrap_or_default(),
+            worklog::total(&task.worklog, Utc::now()).num_minutes().to_string(),
+            task.estimate.map(|estimate| estimate.to_string()).unwrap_or_default(),
+            task.comments.len().to_string(),
+            task.project,
+        ];
+        output.push_str(&row.iter().map(|value| csv_field(value)).collect::<Vec<_>>().join(","));
+        output.push('\n');
+    }
+
+    Ok(output)
+}
+
+/// Renders the tasks matching `query` as a JSON array in the stored task format.
+pub fn export_json(app: &mut App, query: &Query) -> Result<String>{
+    let tasks = matching(app, false, query, &|_| true)?;
+
+    Ok(serde_json::to_string_pretty(&tasks)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        paths::TempDir,
+        store::JsonStore
+    };
+
+    fn app(dir: &TempDir) -> App{
+        App::open(Some(dir.path.clone()), None).unwrap()
+    }
+
+    /// Adds a task as its own operation, the way each command is one.
+    fn add_task(app: &mut App, title: &str) -> i32{
+        app.begin_operation(Action::Change);
+        add(app, Task::new(Priority::Medium, title.to_string(), String::new(), "2026-10-20".parse().unwrap(), vec!["alice".to_string()])).unwrap()
+    }
+
+    fn set_status(app: &mut App, id: i32, status: Status) -> Result<StatusChange>{
+        app.begin_operation(Action::Change);
+        change_status(app, &Target::Id(id), status)
+    }
+
+    fn titles(store: &mut dyn TaskStore) -> Vec<String>{
+        store.load().unwrap().into_iter().map(|t| t.title).collect()
+    }
+
+    #[test]
+    fn undo_and_redo_walk_through_operations(){
+        let dir = TempDir::new();
+        let mut app = app(&dir);
+        let id = add_task(&mut app, "Deploy");
+        set_status(&mut app, id, Status::InProgress).unwrap();
+        set_status(&mut app, id, Status::Completed).unwrap();
+
+        undo(&mut app, true).unwrap();
+        assert_eq!(find(&mut app, id).unwrap().status, Status::InProgress);
+        undo(&mut app, true).unwrap();
+        assert_eq!(find(&mut app, id).unwrap().status, Status::NotCompleted);
+        redo(&mut app, true).unwrap();
+        assert_eq!(find(&mut app, id).unwrap().status, Status::InProgress);
+
+        // Undoing the add takes the task out again, to the trash.
+        undo(&mut app, true).unwrap();
+        undo(&mut app, true).unwrap();
+        assert!(app.store.load().unwrap().is_empty());
+        assert_eq!(titles(app.trash.as_mut()), ["Deploy"]);
+        assert!(undo(&mut app, true).is_err());
+    }
+
+    #[test]
+    fn undo_refuses_tasks_changed_since(){
+        let dir = TempDir::new();
+        let mut app = app(&dir);
+        let id = add_task(&mut app, "Deploy");
+        set_status(&mut app, id, Status::InProgress).unwrap();
+
+        // Another process changes the task without the change being recorded.
+        let mut other = JsonStore::new(dir.path.join("tasks.json"));
+        let mut task = other.load().unwrap().remove(0);
+        task.title = "Deploy everything".to_string();
+        other.update(&task).unwrap();
+
+        app.begin_operation(Action::Change);
+        assert!(undo(&mut app, true).is_err());
+        assert_eq!(find(&mut app, id).unwrap().status, Status::InProgress);
+    }
+
+    #[test]
+    fn ids_of_removed_and_archived_tasks_are_not_reused(){
+        let dir = TempDir::new();
+        let mut app = app(&dir);
+        let first = add_task(&mut app, "Deploy");
+        remove(&mut app, first, None).unwrap();
+        let second = add_task(&mut app, "Review");
+        assert_eq!(second, first + 1);
+
+        set_status(&mut app, second, Status::Completed).unwrap();
+        archive(&mut app, Some(&Target::Id(second))).unwrap();
+        assert_eq!(add_task(&mut app, "Release"), second + 1);
+    }
+
+    #[test]
+    fn removed_tasks_wait_in_the_trash(){
+        let dir = TempDir::new();
+        let mut app = app(&dir);
+        let first = add_task(&mut app, "Deploy");
+        let second = add_task(&mut app, "Review");
+
+        app.begin_operation(Action::Change);
+        assert_eq!(remove(&mut app, first, None).unwrap(), 1);
+        assert_eq!(titles(app.store.as_mut()), ["Review"]);
+        assert_eq!(titles(app.trash.as_mut()), ["Deploy"]);
+
+        // A task taking the removed task's ID sends it back under a new one.
+        app.begin_operation(Action::Change);
+        let mut task = find(&mut app, second).unwrap();
+        task.id = first;
+        update(&mut app, &task).unwrap();
+        app.begin_operation(Action::Change);
+        let restored = restore(&mut app, first, None).unwrap();
+        assert_eq!(restored.id, 2);
+        assert_eq!(restored.deleted_at, None);
+        assert!(app.trash.load().unwrap().is_empty());
+
+        let history = history(&mut app, restored.id).unwrap();
+        assert_eq!(history.last().map(|entry| entry.field.as_str()), Some("restored"));
+    }
+
+    #[test]
+    fn purging_empties_the_trash(){
+        let dir = TempDir::new();
+        let mut app = app(&dir);
+        for title in ["Deploy", "Review", "Release"] {
+            let id = add_task(&mut app, title);
+            remove(&mut app, id, None).unwrap();
+        }
+
+        assert_eq!(purge(&mut app, Some(2), None).unwrap(), 1);
+        assert_eq!(titles(app.trash.as_mut()), ["Deploy", "Release"]);
+        assert!(matches!(purge(&mut app, Some(2), None), Err(Error::NotFound(2))));
+        assert_eq!(purge(&mut app, None, None).unwrap(), 2);
+        assert!(restore(&mut app, 1, None).is_err());
+    }
+
+    #[test]
+    fn only_closed_tasks_are_archived(){
+        let dir = TempDir::new();
+        let mut app = app(&dir);
+        let open = add_task(&mut app, "Deploy");
+        let done = add_task(&mut app, "Review");
+        set_status(&mut app, done, Status::Completed).unwrap();
+
+        app.begin_operation(Action::Change);
+        assert!(archive(&mut app, Some(&Target::Id(open))).is_err());
+        assert_eq!(archive(&mut app, None).unwrap(), 1);
+        assert_eq!(titles(app.store.as_mut()), ["Deploy"]);
+        assert_eq!(titles(app.archive.as_mut()), ["Review"]);
+
+        app.begin_operation(Action::Change);
+        let task = unarchive(&mut app, done, None).unwrap();
+        assert_eq!((task.id, task.archived_at), (done, None));
+        assert!(app.archive.load().unwrap().is_empty());
+
+        // Undoing the unarchive sends the task back to the archive, not the trash.
+        undo(&mut app, true).unwrap();
+        assert_eq!(titles(app.archive.as_mut()), ["Review"]);
+        assert!(app.trash.load().unwrap().is_empty());
+    }
+
+    #[test]
+    fn undo_gets_past_auto_archiving(){
+        let dir = TempDir::new();
+        let mut app = app(&dir);
+        app.config.archive.after_days = 1;
+        let id = add_task(&mut app, "Deploy");
+        set_status(&mut app, id, Status::Completed).unwrap();
+
+        // Pretend the task was closed two days ago.
+        let mut task = find(&mut app, id).unwrap();
+        for transition in &mut task.status_history {
+            transition.at -= Duration::days(2);
+        }
+        app.store.update(&task).unwrap();
+
+        app.begin_operation(Action::Change);
+        assert_eq!(auto_archive(&mut app).unwrap(), 1);
+        app.begin_operation(Action::Change);
+        undo(&mut app, true).unwrap();
+        assert_eq!(titles(app.store.as_mut()), ["Deploy"]);
+
+        // The task an undo brought back stays, so the next undo reopens it.
+        app.begin_operation(Action::Change);
+        assert_eq!(auto_archive(&mut app).unwrap(), 0);
+        undo(&mut app, true).unwrap();
+        assert_eq!(find(&mut app, id).unwrap().status, Status::NotCompleted);
+    }
+
+    #[test]
+    fn editing_the_status_keeps_to_the_blocker_rule(){
+        let dir = TempDir::new();
+        let mut app = app(&dir);
+        app.config.dependencies.on_complete = BlockedRule::Refuse;
+        let blocker = add_task(&mut app, "Deploy");
+        let blocked = add_task(&mut app, "Announce");
+        block(&mut app, blocked, &[blocker.to_string()], false).unwrap();
+
+        let edits = [(Field::Title, "Announce it".to_string()), (Field::Status, "Completed".to_string())];
+        assert!(edit(&mut app, &Target::Id(blocked), &edits).is_err());
+        let task = find(&mut app, blocked).unwrap();
+        assert_eq!((task.title.as_str(), task.status), ("Announce", Status::NotCompleted));
+
+        set_status(&mut app, blocker, Status::Completed).unwrap();
+        let (edited, change) = edit(&mut app, &Target::Id(blocked), &edits).unwrap();
+        assert_eq!((edited, change.map(|change| change.changed)), (1, Some(1)));
+        let task = find(&mut app, blocked).unwrap();
+        assert_eq!((task.title.as_str(), task.status), ("Announce it", Status::Completed));
+    }
+
+    #[test]
+    fn completing_by_edit_schedules_the_next_occurrence(){
+        let dir = TempDir::new();
+        let mut app = app(&dir);
+        let id = add_task(&mut app, "Water plants");
+        let mut task = find(&mut app, id).unwrap();
+        task.recurrence = recur::parse("daily").unwrap();
+        update(&mut app, &task).unwrap();
+
+        app.begin_operation(Action::Change);
+        let (_, change) = edit(&mut app, &Target::Id(id), &[(Field::Status, "completed".to_string())]).unwrap();
+        let next = &change.unwrap().next;
+        assert_eq!(next.len(), 1);
+        assert_eq!(next[0].due_date, "2026-10-21".parse().unwrap());
+        assert_eq!(next[0].series, Some(task.uuid));
+        assert_eq!(titles(app.store.as_mut()), ["Water plants", "Water plants"]);
+    }
+
+    #[test]
+    fn closing_by_edit_applies_the_subtask_rule_and_stops_timers(){
+        let dir = TempDir::new();
+        let mut app = app(&dir);
+        let parent = add_task(&mut app, "Release");
+        let child = add_task(&mut app, "Changelog");
+        edit(&mut app, &Target::Id(child), &[(Field::Parent, parent.to_string())]).unwrap();
+        start_timer(&mut app, parent, None).unwrap();
+        let close = [(Field::Status, "Cancelled".to_string())];
+
+        app.config.subtasks.on_complete = SubtaskRule::Refuse;
+        assert!(edit(&mut app, &Target::Id(parent), &close).is_err());
+        assert_eq!(find(&mut app, parent).unwrap().status, Status::NotCompleted);
+
+        app.config.subtasks.on_complete = SubtaskRule::Cascade;
+        let (_, change) = edit(&mut app, &Target::Id(parent), &close).unwrap();
+        assert_eq!(change.map(|change| change.changed), Some(2));
+        assert_eq!(find(&mut app, child).unwrap().status, Status::Cancelled);
+        assert!(find(&mut app, parent).unwrap().running_timers().is_empty());
+    }
+
+    #[test]
+    fn undo_changes_nothing_when_an_id_was_taken(){
+        let dir = TempDir::new();
+        let mut app = app(&dir);
+        for title in ["Deploy", "Review", "Release"] {
+            add_task(&mut app, title);
+        }
+        app.begin_operation(Action::Change);
+        remove_matching(&mut app, &Query::parse("deploy or release").unwrap()).unwrap();
+
+        // Another process gives the remaining task the ID of a removed one.
+        let mut other = JsonStore::new(dir.path.join("tasks.json"));
+        let mut task = other.load().unwrap().remove(0);
+        task.id = 3;
+        other.update(&task).unwrap();
+
+        app.begin_operation(Action::Change);
+        assert!(undo(&mut app, true).is_err());
+        assert_eq!(titles(app.store.as_mut()), ["Review"]);
+        assert_eq!(titles(app.trash.as_mut()), ["Deploy", "Release"]);
+    }
+
+    #[test]
+    fn check_id_free_refuses_an_id_another_task_uses(){
+        let dir = TempDir::new();
+        let mut app = app(&dir);
+        add_task(&mut app, "Deploy");
+        add_task(&mut app, "Review");
+
+        let mut task = find(&mut app, 2).unwrap();
+        assert!(check_id_free(&mut app, &task).is_ok());
+        task.id = 1;
+        assert!(matches!(check_id_free(&mut app, &task), Err(Error::DuplicateId(1))));
+        task.id = 3;
+        assert!(check_id_free(&mut app, &task).is_ok());
+    }
+}
@@ -0,0 +1,296 @@
+use serde::{
+    Serialize,
+    Deserialize
+};
+use owo_colors::DynColors;
+use std::{
+    collections::BTreeMap,
+    env,
+    fs,
+    io::ErrorKind,
+    path::Path
+};
+
+use crate::{
+    error::{
+        Error,
+        Result
+    },
+    task::{
+        self,
+        Status,
+        Task
+    },
+    view::ListOptions
+};
+
+pub const CONFIG_FILE: &str = "config.json";
+
+#[derive(Debug,Default,Clone,Copy,Serialize,Deserialize,PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend{
+    /// A single `tasks.json` file, rewritten on every change.
+    #[default]
+    Json,
+    /// An embedded `tasks.db` SQLite database with one row per task.
+    Sqlite,
+}
+
+/// What happens to the subtasks of a task that is closed (completed or cancelled) or removed.
+#[derive(Debug,Default,Clone,Copy,Serialize,Deserialize,PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtaskRule{
+    /// Leave subtasks alone. Subtasks of a removed task move up to its parent.
+    #[default]
+    Keep,
+    /// Close or remove every subtask along with the task.
+    Cascade,
+    /// Refuse while the task has open (when closing) or any (when removing) subtasks.
+    Refuse,
+}
+
+#[derive(Debug,Default,Serialize,Deserialize)]
+#[serde(default)]
+pub struct Subtasks{
+    pub on_complete:    SubtaskRule,
+    pub on_remove:      SubtaskRule,
+}
+
+/// What happens when a task is completed while tasks blocking it are still open.
+#[derive(Debug,Default,Clone,Copy,Serialize,Deserialize,PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockedRule{
+    /// Complete it anyway and print a warning.
+    #[default]
+    Warn,
+    /// Refuse until the blockers are completed.
+    Refuse,
+}
+
+/// How long removed tasks stay in the trash.
+#[derive(Debug,Serialize,Deserialize)]
+#[serde(default)]
+pub struct Trash{
+    /// Days before a removed task is purged for good; 0 keeps it until purged by hand.
+    pub retention_days: u32,
+}
+
+impl Default for Trash{
+    fn default() -> Trash{
+        Trash{ retention_days: 30 }
+    }
+}
+
+/// When closed tasks are archived without being asked to.
+#[derive(Debug,Default,Serialize,Deserialize)]
+#[serde(default)]
+pub struct Archive{
+    /// Days after they are closed that tasks are archived; 0 only archives by hand.
+    pub after_days:     u32,
+}
+
+#[derive(Debug,Default,Serialize,Deserialize)]
+#[serde(default)]
+pub struct Dependencies{
+    pub on_complete:    BlockedRule,
+}
+
+/// Custom statuses and the status changes that are allowed.
+#[derive(Debug,Default,Serialize,Deserialize)]
+#[serde(default)]
+pub struct Statuses{
+    /// Extra statuses next to the built-in ones, e.g. `Waiting`. They count as open.
+    pub custom:         Vec<String>,
+    /// The statuses each status may change to. A status that is not listed may
+    /// change to any status.
+    pub transitions:    BTreeMap<String, Vec<String>>,
+}
+
+impl Statuses{
+    /// Every status a task can have, the built-in ones first.
+    pub fn all(&self) -> Vec<Status>{
+        Status::BUILT_IN.into_iter()
+            .chain(self.custom.iter().map(|name| Status::Custom(name.clone())))
+            .collect()
+    }
+
+    /// Matches a parsed status against the known ones, ignoring the case of custom names.
+    pub fn resolve(&self, status: Status) -> Result<Status>{
+        let Status::Custom(name) = &status else {
+            return Ok(status);
+        };
+
+        self.custom.iter()
+            .find(|custom| custom.eq_ignore_ascii_case(name))
+            .map(|custom| Status::Custom(custom.clone()))
+            .ok_or_else(|| Error::Invalid(format!("Invalid status: {}, expected one of: {}", name, self.all().iter().map(Status::name).collect::<Vec<_>>().join(", "))))
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Status>{
+        self.resolve(task::parse_status(input)?)
+    }
+
+    /// The statuses a task with status `from` may change to.
+    pub fn allowed(&self, from: &Status) -> Vec<Status>{
+        let listed = self.transitions.iter()
+            .find(|(name, _)| self.parse(name).is_ok_and(|status| status == *from));
+
+        match listed {
+            Some((_, targets))  => targets.iter().filter_map(|name| self.parse(name).ok()).collect(),
+            None                => self.all().into_iter().filter(|status| status != from).collect(),
+        }
+    }
+
+    /// Refuses a status change the transitions do not allow.
+    pub fn check(&self, from: &Status, to: &Status) -> Result<()>{
+        if from == to || self.allowed(from).contains(to) {
+            return Ok(());
+        }
+
+        let allowed: Vec<String> = self.allowed(from).iter().map(|status| status.to_string()).collect();
+        Err(Error::Invalid(format!("A task cannot change from {} to {}, it can change to: {}", from, to, match allowed.is_empty() {
+            true    => "nothing".to_string(),
+            false   => allowed.join(", "),
+        })))
+    }
+
+    /// Checks that custom names do not clash with built-in ones and that the
+    /// transitions only name known statuses.
+    fn validate(&self) -> Result<()>{
+        for name in &self.custom {
+            if !matches!(task::parse_status(name)?, Status::Custom(_)) {
+                return Err(Error::Invalid(format!("Custom status {} in {} is already a built-in status", name, CONFIG_FILE)));
+            }
+        }
+        for (from, targets) in &self.transitions {
+            for name in std::iter::once(from).chain(targets) {
+                self.parse(name).map_err(|err| Error::Invalid(format!("{} in the status transitions of {}", err, CONFIG_FILE)))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Settings of one project.
+#[derive(Debug,Default,Serialize,Deserialize)]
+#[serde(default)]
+pub struct Project{
+    /// Replaces `id_prefix` for the project's tasks.
+    pub id_prefix:      Option<String>,
+}
+
+/// User settings read from `config.json` in the data directory. Missing keys
+/// (or a missing file) fall back to the defaults.
+#[derive(Debug,Default,Serialize,Deserialize)]
+#[serde(default)]
+pub struct Config{
+    pub backend:        Backend,
+    /// Shown before task IDs, e.g. `OPS` displays task 42 as `OPS-42`.
+    pub id_prefix:      Option<String>,
+    /// Default sorting, grouping and layout of `list`.
+    pub list:           ListOptions,
+    pub subtasks:       Subtasks,
+    pub dependencies:   Dependencies,
+    pub trash:          Trash,
+    pub archive:        Archive,
+    /// Colours tags are shown in: a name such as `red` or `bright blue`, or `#rrggbb`.
+    pub tag_colors:     BTreeMap<String, String>,
+    pub statuses:       Statuses,
+    /// The name comments are written under, unless `$TERMTASKER_USER` is set.
+    pub user:           Option<String>,
+    /// Settings per project name.
+    pub projects:       BTreeMap<String, Project>,
+}
+
+impl Config{
+    /// The prefix the IDs of tasks in `project` are shown with: the project's
+    /// own, or else `id_prefix`.
+    pub fn prefix(&self, project: &str) -> Option<&str>{
+        self.projects.get(project)
+            .and_then(|settings| settings.id_prefix.as_deref())
+            .or(self.id_prefix.as_deref())
+    }
+
+    /// Formats the ID of a task in `project` the way the user refers to it,
+    /// with the project's own prefix if it has one.
+    pub fn project_key(&self, project: &str, id: i32) -> String{
+        match self.prefix(project) {
+            Some(prefix)    => format!("{}-{}", prefix, id),
+            None            => id.to_string(),
+        }
+    }
+
+    /// Formats a task's ID the way the user refers to it.
+    pub fn key(&self, task: &Task) -> String{
+        self.project_key(&task.project, task.id)
+    }
+
+    /// Who is using TermTasker: `$TERMTASKER_USER`, the `user` setting, or the
+    /// login name.
+    pub fn user(&self) -> String{
+        env::var("TERMTASKER_USER").ok()
+            .or_else(|| self.user.clone())
+            .or_else(|| env::var("USER").ok())
+            .or_else(|| env::var("USERNAME").ok())
+            .filter(|user| !user.trim().is_empty())
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+
+    /// The colour configured for `tag`, if any.
+    pub fn tag_color(&self, tag: &str) -> Option<DynColors>{
+        self.tag_colors.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(tag))?
+            .1
+            .parse()
+            .ok()
+    }
+
+    pub fn load(dir: &Path) -> Result<Config>{
+        let config: Config = match fs::read_to_string(dir.join(CONFIG_FILE)) {
+            Ok(contents)                                    => serde_json::from_str(&contents)?,
+            Err(err) if err.kind() == ErrorKind::NotFound   => Config::default(),
+            Err(err)                                        => return Err(err.into()),
+        };
+
+        config.statuses.validate()?;
+        // IDs are read back as `<prefix>-<number>`, see `task::parse_key`.
+        let prefixes = config.id_prefix.iter()
+            .map(|prefix| ("id_prefix".to_string(), prefix))
+            .chain(config.projects.iter().filter_map(|(name, settings)| Some((format!("the id_prefix of project {}", name), settings.id_prefix.as_ref()?))));
+        for (setting, prefix) in prefixes {
+            if prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return Err(Error::Invalid(format!("Invalid {} in {}: '{}', expected letters and digits such as OPS or WEB2", setting, CONFIG_FILE, prefix)));
+            }
+        }
+        for (tag, color) in &config.tag_colors {
+            if color.parse::<DynColors>().is_err() {
+                return Err(Error::Invalid(format!("Invalid colour for tag {} in {}: {}, expected a name such as red or bright blue, or #rrggbb", tag, CONFIG_FILE, color)));
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(contents: &str) -> Result<Config>{
+        let dir = crate::paths::TempDir::new();
+        fs::write(dir.path.join(CONFIG_FILE), contents).unwrap();
+        Config::load(&dir.path)
+    }
+
+    #[test]
+    fn prefixes_have_to_be_readable_back(){
+        let config = load(r#"{ "id_prefix": "OPS", "projects": { "web": { "id_prefix": "WEB2" } } }"#).unwrap();
+        assert_eq!(config.project_key("web", 4), "WEB2-4");
+        assert_eq!(config.project_key("api", 4), "OPS-4");
+
+        for contents in [r#"{ "id_prefix": "" }"#, r#"{ "id_prefix": "OPS-1" }"#, r#"{ "projects": { "web": { "id_prefix": "WEB_X" } } }"#] {
+            assert!(load(contents).is_err(), "{} was accepted", contents);
+        }
+    }
+}
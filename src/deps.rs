@@ -0,0 +1,136 @@
+use std::collections::{
+    HashMap,
+    HashSet
+};
+use uuid::Uuid;
+
+use crate::task::Task;
+
+/// The blockers of `task` that are still open. Blockers that no longer exist
+/// are ignored.
+pub fn open_blockers<'a>(all: &'a [Task], task: &Task) -> Vec<&'a Task>{
+    all.iter()
+        .filter(|t| task.blocked_by.contains(&t.uuid) && t.status.is_open())
+        .collect()
+}
+
+/// The tasks each task blocks, by the UUID of the blocker, built in one pass
+/// so a listing does not search every task for every row.
+pub fn dependents(all: &[Task]) -> HashMap<Uuid, Vec<&Task>>{
+    let mut dependents: HashMap<Uuid, Vec<&Task>> = HashMap::new();
+    for task in all {
+        for blocker in &task.blocked_by {
+            dependents.entry(*blocker).or_default().push(task);
+        }
+    }
+    dependents
+}
+
+/// Returns a task that ends up waiting on itself, if the dependencies form a cycle.
+pub fn find_cycle(tasks: &[Task]) -> Option<&Task>{
+    let blockers: HashMap<Uuid, &[Uuid]> = tasks.iter()
+        .map(|task| (task.uuid, task.blocked_by.as_slice()))
+        .collect();
+
+    tasks.iter().find(|task| {
+        let mut pending: Vec<Uuid> = task.blocked_by.clone();
+        let mut seen = HashSet::new();
+
+        while let Some(uuid) = pending.pop() {
+            if uuid == task.uuid {
+                return true;
+            }
+            if seen.insert(uuid) {
+                pending.extend(blockers.get(&uuid).copied().unwrap_or_default());
+            }
+        }
+        false
+    })
+}
+
+/// Orders `tasks` so every task comes after the ones among them that block it.
+/// Among tasks that could go next, the earlier one in `tasks` wins, so sort
+/// them by urgency first. Tasks caught in a cycle keep their relative order at the end.
+pub fn order(tasks: Vec<Task>) -> Vec<Task>{
+    let present: HashSet<Uuid> = tasks.iter().map(|t| t.uuid).collect();
+    let mut waiting: Vec<usize> = tasks.iter()
+        .map(|task| task.blocked_by.iter().filter(|uuid| present.contains(uuid)).count())
+        .collect();
+
+    let mut placed = vec![false; tasks.len()];
+    let mut order = Vec::with_capacity(tasks.len());
+
+    while let Some(next) = (0..tasks.len()).find(|index| !placed[*index] && waiting[*index] == 0) {
+        placed[next] = true;
+        order.push(next);
+        for (index, task) in tasks.iter().enumerate() {
+            if !placed[index] && task.blocked_by.contains(&tasks[next].uuid) {
+                waiting[index] -= 1;
+            }
+        }
+    }
+    order.extend((0..tasks.len()).filter(|index| !placed[*index]));
+
+    let mut tasks: Vec<Option<Task>> = tasks.into_iter().map(Some).collect();
+    order.into_iter()
+        .filter_map(|index| tasks[index].take())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{
+        Priority,
+        Status
+    };
+
+    fn tasks(count: i32) -> Vec<Task>{
+        (1..=count)
+            .map(|id| {
+                let mut task = Task::new(Priority::Medium, format!("Task {}", id), String::new(), "2026-10-20".parse().unwrap(), Vec::new());
+                task.id = id;
+                task
+            })
+            .collect()
+    }
+
+    fn ids(tasks: &[Task]) -> Vec<i32>{
+        tasks.iter().map(|t| t.id).collect()
+    }
+
+    #[test]
+    fn only_open_blockers_count(){
+        let mut tasks = tasks(3);
+        tasks[2].blocked_by = vec![tasks[0].uuid, tasks[1].uuid];
+        tasks[1].status = Status::Completed;
+
+        let blockers: Vec<i32> = open_blockers(&tasks, &tasks[2]).iter().map(|t| t.id).collect();
+        assert_eq!(blockers, [1]);
+        let blocked: Vec<i32> = dependents(&tasks)[&tasks[1].uuid].iter().map(|t| t.id).collect();
+        assert_eq!(blocked, [3]);
+    }
+
+    #[test]
+    fn cycles_are_found(){
+        let mut tasks = tasks(3);
+        tasks[1].blocked_by = vec![tasks[0].uuid];
+        tasks[2].blocked_by = vec![tasks[1].uuid];
+        assert!(find_cycle(&tasks).is_none());
+
+        tasks[0].blocked_by = vec![tasks[2].uuid];
+        assert!(find_cycle(&tasks).is_some());
+
+        let mut tasks = self::tasks(1);
+        tasks[0].blocked_by = vec![tasks[0].uuid];
+        assert_eq!(find_cycle(&tasks).map(|t| t.id), Some(1));
+    }
+
+    #[test]
+    fn blockers_come_first(){
+        let mut tasks = tasks(4);
+        tasks[0].blocked_by = vec![tasks[3].uuid];
+        tasks[1].blocked_by = vec![tasks[0].uuid, tasks[2].uuid];
+        assert_eq!(ids(&order(tasks)), [3, 4, 1, 2]);
+    }
+}
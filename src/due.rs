@@ -0,0 +1,262 @@
+use chrono::{
+    DateTime,
+    Datelike,
+    Days,
+    Months,
+    NaiveDate,
+    NaiveTime,
+    TimeZone,
+    Utc,
+    Weekday
+};
+use chrono_tz::Tz;
+use serde::{
+    Serialize,
+    Deserialize
+};
+use std::{
+    env,
+    fmt
+};
+
+use crate::error::{
+    Error,
+    Result
+};
+
+pub const DATES: &str = "YYYY-MM-DD, today, tomorrow, fri, next monday, +3d, in 2 weeks or end of month";
+
+/// A due time, stored as the instant it falls on together with the time zone
+/// it was given in, so it can be shown in the viewer's zone and moved to
+/// another date at the same wall-clock time.
+#[derive(Debug,Clone,PartialEq,Serialize,Deserialize)]
+pub struct DueTime{
+    pub at:     DateTime<Utc>,
+    pub zone:   Tz,
+}
+
+impl DueTime{
+    /// `time` on `date` in `zone`. Times skipped by a daylight saving change
+    /// move to the next valid time.
+    pub fn new(date: NaiveDate, time: NaiveTime, zone: Tz) -> DueTime{
+        let mut local = date.and_time(time);
+        let at = loop {
+            match zone.from_local_datetime(&local).earliest() {
+                Some(at)    => break at.with_timezone(&Utc),
+                None        => local += chrono::Duration::minutes(15),
+            }
+        };
+
+        DueTime{ at, zone }
+    }
+
+    /// The date in the zone the due time was given in.
+    pub fn date(&self) -> NaiveDate{
+        self.at.with_timezone(&self.zone).date_naive()
+    }
+
+    /// The wall-clock time in the zone the due time was given in.
+    pub fn time(&self) -> NaiveTime{
+        self.at.with_timezone(&self.zone).time()
+    }
+
+    /// The same wall-clock time and zone on another date.
+    pub fn on(&self, date: NaiveDate) -> DueTime{
+        DueTime::new(date, self.time(), self.zone)
+    }
+}
+
+/// Shown in the viewer's zone, followed by the original time and zone when they differ.
+impl fmt::Display for DueTime{
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        let local_zone = local_zone();
+        let local = self.at.with_timezone(&local_zone);
+        write!(f, "{}", local.format("%Y-%m-%d %H:%M %Z"))?;
+
+        if self.zone != local_zone {
+            let original = self.at.with_timezone(&self.zone);
+            write!(f, " ({} {})", original.format("%H:%M"), self.zone)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The viewer's time zone: `$TZ` when it names a zone, else the system zone, else UTC.
+pub fn local_zone() -> Tz{
+    env::var("TZ").ok()
+        .and_then(|name| name.trim_start_matches(':').parse().ok())
+        .or_else(|| iana_time_zone::get_timezone().ok()?.parse().ok())
+        .unwrap_or(Tz::UTC)
+}
+
+pub fn parse_zone(input: &str) -> Result<Tz>{
+    input.trim().parse().map_err(|_| Error::Invalid(format!("Invalid time zone: {}, expected a name such as Europe/Berlin or UTC", input)))
+}
+
+/// Parses `HH:MM`, optionally followed by a time zone name (the viewer's zone
+/// otherwise), e.g. `15:00 America/New_York`.
+pub fn parse_time(input: &str) -> Result<(NaiveTime, Tz)>{
+    let mut parts = input.split_whitespace();
+    let time = parts.next().unwrap_or_default();
+    let time = NaiveTime::parse_from_str(time, "%H:%M")
+        .map_err(|_| Error::Invalid(format!("Invalid due time: {}, expected HH:MM optionally followed by a time zone", input)))?;
+
+    let zone = match parts.next() {
+        Some(zone)  => parse_zone(zone)?,
+        None        => local_zone(),
+    };
+    if parts.next().is_some() {
+        return Err(Error::Invalid(format!("Invalid due time: {}, expected HH:MM optionally followed by a time zone", input)));
+    }
+
+    Ok((time, zone))
+}
+
+/// Parses a due time for a task due on `date`, or `none` (or nothing) for a
+/// task due some time that day.
+pub fn parse(input: &str, date: NaiveDate) -> Result<Option<DueTime>>{
+    match input.trim() {
+        "" | "none" => Ok(None),
+        time        => {
+            let (time, zone) = parse_time(time)?;
+            Ok(Some(DueTime::new(date, time, zone)))
+        }
+    }
+}
+
+/// Today in the viewer's zone.
+pub fn today() -> NaiveDate{
+    Utc::now().with_timezone(&local_zone()).date_naive()
+}
+
+/// Moves `date` by `count` days, weeks, months or years; month and year steps
+/// that land past the end of a month stop at its last day.
+fn shift(date: NaiveDate, count: i64, unit: &str) -> Option<NaiveDate>{
+    let steps = count.unsigned_abs();
+    let (days, months) = match unit {
+        "" | "d" | "day" | "days"   => (steps, 0),
+        "w" | "week" | "weeks"      => (steps.checked_mul(7)?, 0),
+        "m" | "month" | "months"    => (0, steps),
+        "y" | "year" | "years"      => (0, steps.checked_mul(12)?),
+        _                           => return None,
+    };
+    let months = Months::new(u32::try_from(months).ok()?);
+
+    match count < 0 {
+        false   => date.checked_add_days(Days::new(days))?.checked_add_months(months),
+        true    => date.checked_sub_days(Days::new(days))?.checked_sub_months(months),
+    }
+}
+
+/// The last day of the week (Sunday), month or year `date` falls in.
+fn end_of(date: NaiveDate, unit: &str) -> Option<NaiveDate>{
+    match unit {
+        "week"  => date.checked_add_days(Days::new(u64::from(6 - date.weekday().num_days_from_monday()))),
+        "month" => date.with_day(1)?.checked_add_months(Months::new(1))?.pred_opt(),
+        "year"  => NaiveDate::from_ymd_opt(date.year(), 12, 31),
+        _       => None,
+    }
+}
+
+/// Parses a due date relative to `today`: an ISO date, `today`, `tomorrow`,
+/// `yesterday`, a weekday (`fri`, `next monday`: the first one after today),
+/// `next week`/`month`/`year`, an offset (`+3d`, `-1w`, `+2m`, `in 2 weeks`,
+/// `in a month`) or `end of week`/`month`/`year`.
+pub fn parse_date(input: &str, today: NaiveDate) -> Result<NaiveDate>{
+    let text = input.trim().to_lowercase();
+    if let Ok(date) = NaiveDate::parse_from_str(&text, "%Y-%m-%d") {
+        return Ok(date);
+    }
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let date = match words.as_slice() {
+        ["today"]                   => Some(today),
+        ["tomorrow"]                => today.succ_opt(),
+        ["yesterday"]               => today.pred_opt(),
+        ["next", unit @ ("week" | "month" | "year")] => shift(today, 1, unit),
+        ["end", "of", unit]         => end_of(today, unit),
+        ["in", count, unit]         => match *count {
+            "a" | "an"  => shift(today, 1, unit),
+            count       => count.parse().ok().and_then(|count| shift(today, count, unit)),
+        },
+        [offset] if offset.starts_with(['+', '-']) => {
+            let unit_start = offset.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(offset.len());
+            let (count, unit) = offset.split_at(unit_start);
+            count.trim_start_matches('+').parse().ok().and_then(|count| shift(today, count, unit))
+        }
+        [day] | ["next", day]       => day.parse::<Weekday>().ok().and_then(|weekday| {
+            (1..=7).map(|offset| today + Days::new(offset)).find(|date| date.weekday() == weekday)
+        }),
+        _                           => None,
+    };
+
+    date.ok_or_else(|| Error::Invalid(format!("Invalid due date: {}, expected {}", input, DATES)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A Sunday.
+    fn today() -> NaiveDate{
+        date("2026-10-18")
+    }
+
+    fn date(text: &str) -> NaiveDate{
+        text.parse().unwrap()
+    }
+
+    fn parsed(input: &str) -> NaiveDate{
+        parse_date(input, today()).unwrap()
+    }
+
+    #[test]
+    fn iso_dates_and_named_days(){
+        assert_eq!(parsed("2027-02-03"), date("2027-02-03"));
+        assert_eq!(parsed("today"), today());
+        assert_eq!(parsed("Tomorrow"), date("2026-10-19"));
+        assert_eq!(parsed("yesterday"), date("2026-10-17"));
+    }
+
+    #[test]
+    fn weekdays_are_the_first_one_after_today(){
+        assert_eq!(parsed("fri"), date("2026-10-23"));
+        assert_eq!(parsed("next monday"), date("2026-10-19"));
+        assert_eq!(parsed("sunday"), date("2026-10-25"));
+    }
+
+    #[test]
+    fn offsets(){
+        assert_eq!(parsed("+3d"), date("2026-10-21"));
+        assert_eq!(parsed("+3"), date("2026-10-21"));
+        assert_eq!(parsed("-1w"), date("2026-10-11"));
+        assert_eq!(parsed("+2m"), date("2026-12-18"));
+        assert_eq!(parsed("+1y"), date("2027-10-18"));
+        assert_eq!(parsed("in 2 weeks"), date("2026-11-01"));
+        assert_eq!(parsed("in a month"), date("2026-11-18"));
+        assert_eq!(parsed("next week"), date("2026-10-25"));
+    }
+
+    #[test]
+    fn month_steps_stop_at_the_end_of_the_month(){
+        assert_eq!(parse_date("+1m", date("2026-01-31")).unwrap(), date("2026-02-28"));
+        assert_eq!(parse_date("next year", date("2028-02-29")).unwrap(), date("2029-02-28"));
+    }
+
+    #[test]
+    fn ends_of_periods(){
+        assert_eq!(parsed("end of week"), today());
+        assert_eq!(parse_date("end of week", date("2026-10-19")).unwrap(), today() + Days::new(7));
+        assert_eq!(parsed("end of month"), date("2026-10-31"));
+        assert_eq!(parsed("end of year"), date("2026-12-31"));
+    }
+
+    #[test]
+    fn nonsense_is_rejected(){
+        for input in ["", "soon", "+3x", "in many weeks", "2026-02-30", "end of day", "+99999999999y"] {
+            assert!(parse_date(input, today()).is_err(), "{} was accepted", input);
+        }
+    }
+}
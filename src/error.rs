@@ -0,0 +1,57 @@
+use std::{
+    fmt,
+    io,
+    path::PathBuf
+};
+
+#[derive(Debug)]
+pub enum Error{
+    Io(io::Error),
+    Json(serde_json::Error),
+    Sqlite(rusqlite::Error),
+    NotFound(i32),
+    Ambiguous(i32, Vec<String>),
+    Invalid(String),
+    Conflict(PathBuf),
+    NewerSchema(u32),
+    DuplicateId(i32),
+}
+
+impl fmt::Display for Error{
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        match self{
+            Error::Io(err)              => write!(f, "Error accessing the task file: {}", err),
+            Error::Json(err)            => write!(f, "Error parsing the task file: {}", err),
+            Error::Sqlite(err)          => write!(f, "Error accessing the task database: {}", err),
+            Error::NotFound(id)         => write!(f, "Task with ID: {} not found", id),
+            Error::Ambiguous(id, uuids) => write!(f, "Multiple tasks found with ID: {} (UUIDs: {})", id, uuids.join(", ")),
+            Error::Invalid(message)     => write!(f, "{}", message),
+            Error::Conflict(path)       => write!(f, "{} was changed by another process since it was read, nothing was saved. Please try again", path.display()),
+            Error::NewerSchema(version) => write!(f, "The task data uses schema version {} but this build only supports up to version {}, please upgrade TermTasker", version, crate::schema::VERSION),
+            Error::DuplicateId(id)      => write!(f, "Another task already uses ID: {}", id),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error{
+    fn from(err: io::Error) -> Error{
+        Error::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for Error{
+    fn from(err: serde_json::Error) -> Error{
+        Error::Json(err)
+    }
+}
+
+impl From<rusqlite::Error> for Error{
+    fn from(err: rusqlite::Error) -> Error{
+        Error::Sqlite(err)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
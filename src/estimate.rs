@@ -0,0 +1,109 @@
+use serde::{
+    Serialize,
+    Deserialize
+};
+use std::{
+    fmt,
+    str::FromStr
+};
+
+use crate::{
+    error::{
+        Error,
+        Result
+    },
+    worklog
+};
+
+pub const UNITS: &str = "hours such as 4h or 1h30m, or story points such as 3pt";
+
+/// How much work a task is expected to take, in hours or in story points.
+#[derive(Debug,Clone,Copy,PartialEq,Serialize,Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Estimate{
+    Hours(f64),
+    Points(f64),
+}
+
+impl Estimate{
+    /// The same estimate split between `count` people.
+    pub fn share(&self, count: usize) -> Estimate{
+        let count = count.max(1) as f64;
+        match *self {
+            Estimate::Hours(hours)      => Estimate::Hours(hours / count),
+            Estimate::Points(points)    => Estimate::Points(points / count),
+        }
+    }
+}
+
+impl fmt::Display for Estimate{
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        match *self {
+            Estimate::Hours(hours)      => write!(f, "{}h", (hours * 100.0).round() / 100.0),
+            Estimate::Points(points)    => match points == 1.0 {
+                true    => write!(f, "1 pt"),
+                false   => write!(f, "{} pts", (points * 100.0).round() / 100.0),
+            },
+        }
+    }
+}
+
+impl FromStr for Estimate{
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Estimate>{
+        let invalid = || Error::Invalid(format!("Invalid estimate: {}, expected {}", input, UNITS));
+        let text: String = input.trim().to_lowercase().split_whitespace().collect();
+
+        let points = ["points", "point", "pts", "pt", "sp"].iter()
+            .find_map(|unit| text.strip_suffix(unit));
+        if let Some(points) = points {
+            return match points.parse::<f64>() {
+                Ok(points) if points.is_finite() && points > 0.0    => Ok(Estimate::Points(points)),
+                _                                                   => Err(invalid()),
+            };
+        }
+
+        // A bare number could be hours or points.
+        if text.parse::<f64>().is_ok() {
+            return Err(invalid());
+        }
+        let duration = worklog::parse_duration(&text).map_err(|_| invalid())?;
+        Ok(Estimate::Hours(duration.num_seconds() as f64 / 3600.0))
+    }
+}
+
+/// Parses an estimate, or `none` (or nothing) for a task without one.
+pub fn parse(input: &str) -> Result<Option<Estimate>>{
+    match input.trim() {
+        "" | "none" => Ok(None),
+        estimate    => Ok(Some(estimate.parse()?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hours_and_points(){
+        assert_eq!("4h".parse::<Estimate>().unwrap(), Estimate::Hours(4.0));
+        assert_eq!("1h30m".parse::<Estimate>().unwrap(), Estimate::Hours(1.5));
+        assert_eq!("3 pts".parse::<Estimate>().unwrap(), Estimate::Points(3.0));
+        assert_eq!("2SP".parse::<Estimate>().unwrap(), Estimate::Points(2.0));
+        assert_eq!(parse(" none ").unwrap(), None);
+        for input in ["3", "0pt", "-2pt", "soon"] {
+            assert!(input.parse::<Estimate>().is_err(), "{} was accepted", input);
+        }
+    }
+
+    #[test]
+    fn shares_and_display(){
+        assert_eq!(Estimate::Hours(6.0).share(3), Estimate::Hours(2.0));
+        assert_eq!(Estimate::Points(5.0).share(0), Estimate::Points(5.0));
+        assert_eq!(Estimate::Hours(1.0 / 3.0).to_string(), "0.33h");
+        assert_eq!(Estimate::Points(1.0).to_string(), "1 pt");
+        assert_eq!(Estimate::Points(2.5).to_string(), "2.5 pts");
+    }
+}
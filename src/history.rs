@@ -0,0 +1,396 @@
+use chrono::{
+    DateTime,
+    Utc
+};
+use serde::{
+    Serialize,
+    Deserialize
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    error::Result,
+    schema,
+    task::Task,
+    worklog
+};
+
+/// Why an operation changed tasks.
+#[derive(Debug,Default,Clone,Copy,PartialEq,Serialize,Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action{
+    /// A command or menu option.
+    #[default]
+    Change,
+    /// Reverting the operation with this ID.
+    Undo(Uuid),
+    /// Reapplying the undone operation with this ID.
+    Redo(Uuid),
+}
+
+/// One change to one task, as the store saw it: a task created (no `before`),
+/// deleted (no `after`) or changed.
+#[derive(Debug,Clone)]
+pub struct Event{
+    pub at:     DateTime<Utc>,
+    pub actor:  String,
+    /// The operation, such as one command, the change was part of.
+    pub op:     Uuid,
+    pub action: Action,
+    pub before: Option<Task>,
+    pub after:  Option<Task>,
+}
+
+/// An event as it is stored. The task snapshots keep the schema version they
+/// were written at and are upgraded when read, like the tasks themselves.
+#[derive(Serialize,Deserialize)]
+struct Stored{
+    version:    u32,
+    at:         DateTime<Utc>,
+    actor:      String,
+    /// Missing from events recorded before operations were; each is its own.
+    #[serde(default)]
+    op:         Option<Uuid>,
+    #[serde(default)]
+    action:     Action,
+    before:     Option<Value>,
+    after:      Option<Value>,
+}
+
+/// The events of one operation, in the order they happened.
+pub struct Operation{
+    pub id:     Uuid,
+    pub action: Action,
+    pub events: Vec<Event>,
+}
+
+/// A line of history: one field of a task changing from one value to another.
+pub struct Entry{
+    pub at:         DateTime<Utc>,
+    pub actor:      String,
+    pub id:         i32,
+    pub project:    String,
+    pub title:      String,
+    /// The changed field, or `created`, `deleted`, `archived`, `restored` or
+    /// `unarchived`.
+    pub field:      String,
+    pub old:        String,
+    pub new:        String,
+}
+
+/// Writes one field of a task for its history.
+type Text = fn(&Task) -> String;
+
+/// The fields shown in a task's history, with how each is written.
+const FIELDS: [(&str, Text); 17] = [
+    ("id",          |t| t.id.to_string()),
+    ("priority",    |t| t.priority.to_string()),
+    ("title",       |t| t.title.clone()),
+    ("description", |t| t.description.clone()),
+    ("due_date",    |t| t.due_date.to_string()),
+    ("due_time",    |t| t.due_time.as_ref().map(|time| format!("{} {}", time.time().format("%H:%M"), time.zone)).unwrap_or_default()),
+    ("status",      |t| t.status.to_string()),
+    ("assignees",   |t| t.assignees.join(", ")),
+    ("parent",      |t| t.parent.map(|parent| parent.to_string()).unwrap_or_default()),
+    ("blocked_by",  |t| t.blocked_by.iter().map(Uuid::to_string).collect::<Vec<_>>().join(", ")),
+    ("tags",        |t| t.tags.iter().cloned().collect::<Vec<_>>().join(", ")),
+    ("recurrence",  |t| t.recurrence.as_ref().map(|recurrence| recurrence.to_string()).unwrap_or_default()),
+    ("estimate",    |t| t.estimate.map(|estimate| estimate.to_string()).unwrap_or_default()),
+    ("worklog",     |t| match t.worklog.iter().any(|work| work.is_running()) {
+        true    => format!("{} entries, timer running", t.worklog.len()),
+        false   => format!("{} entries, {}", t.worklog.len(), worklog::format_duration(worklog::total(&t.worklog, Utc::now()))),
+    }),
+    ("comments",    |t| t.comments.len().to_string()),
+    ("project",     |t| t.project.clone()),
+    ("series",      |t| t.series.map(|series| series.to_string()).unwrap_or_default()),
+];
+
+impl Event{
+    pub fn new(actor: &str, op: Uuid, action: Action, before: Option<Task>, after: Option<Task>) -> Event{
+        Event{ at: Utc::now(), actor: actor.to_string(), op, action, before, after }
+    }
+
+    /// The task as it is after the event, or as it was before it was deleted.
+    pub fn task(&self) -> Option<&Task>{
+        self.after.as_ref().or(self.before.as_ref())
+    }
+
+    /// What the event changed, one entry per field.
+    pub fn entries(&self) -> Vec<Entry>{
+        let Some(task) = self.task() else {
+            return Vec::new();
+        };
+        let entry = |field: &str, old: String, new: String| Entry{
+            at:         self.at,
+            actor:      self.actor.clone(),
+            id:         task.id,
+            project:    task.project.clone(),
+            title:      task.title.clone(),
+            field:      field.to_string(),
+            old,
+            new,
+        };
+
+        match (&self.before, &self.after) {
+            (None, Some(after))         => match (after.deleted_at, after.archived_at) {
+                (Some(_), _)    => vec![entry("restored", String::new(), after.title.clone())],
+                (_, Some(_))    => vec![entry("unarchived", String::new(), after.title.clone())],
+                _               => vec![entry("created", String::new(), after.title.clone())],
+            },
+            (Some(before), None)        => match before.archived_at {
+                Some(_) => vec![entry("archived", before.title.clone(), String::new())],
+                None    => vec![entry("deleted", before.title.clone(), String::new())],
+            },
+            (Some(before), Some(after)) => FIELDS.iter()
+                .map(|(field, text)| (field, text(before), text(after)))
+                .filter(|(_, old, new)| old != new)
+                .map(|(field, old, new)| entry(field, old, new))
+                .collect(),
+            (None, None)                => Vec::new(),
+        }
+    }
+}
+
+impl Operation{
+    pub fn entries(&self) -> Vec<Entry>{
+        self.events.iter().flat_map(Event::entries).collect()
+    }
+
+    /// Every task the operation touched, as `(before, after)`: its state before
+    /// the operation's first change to it and after the last.
+    pub fn tasks(&self) -> Vec<(Option<Task>, Option<Task>)>{
+        let mut tasks: Vec<(Uuid, Option<Task>, Option<Task>)> = Vec::new();
+        for event in &self.events {
+            let Some(uuid) = event.task().map(|task| task.uuid) else {
+                continue;
+            };
+            match tasks.iter_mut().find(|(touched, _, _)| *touched == uuid) {
+                Some((_, _, after)) => after.clone_from(&event.after),
+                None                => tasks.push((uuid, event.before.clone(), event.after.clone())),
+            }
+        }
+
+        tasks.into_iter().map(|(_, before, after)| (before, after)).collect()
+    }
+
+    /// The tasks the operation moved to the archive rather than the trash.
+    pub fn archived(&self) -> Vec<Uuid>{
+        self.events.iter()
+            .filter(|event| event.after.is_none())
+            .filter_map(|event| event.before.as_ref())
+            .filter(|task| task.archived_at.is_some())
+            .map(|task| task.uuid)
+            .collect()
+    }
+
+    /// The tasks the operation brought back out of the archive.
+    pub fn unarchived(&self) -> Vec<Uuid>{
+        self.events.iter()
+            .filter(|event| event.before.is_none())
+            .filter_map(|event| event.after.as_ref())
+            .filter(|task| task.archived_at.is_some())
+            .map(|task| task.uuid)
+            .collect()
+    }
+}
+
+/// Groups events into operations, in the order the operations started.
+pub fn operations(events: Vec<Event>) -> Vec<Operation>{
+    let mut operations: Vec<Operation> = Vec::new();
+    for event in events {
+        match operations.iter_mut().rev().find(|operation| operation.id == event.op) {
+            Some(operation) => operation.events.push(event),
+            None            => operations.push(Operation{ id: event.op, action: event.action, events: vec![event] }),
+        }
+    }
+
+    operations
+}
+
+/// Replays the log into the operations that can be undone and those that can
+/// be redone, the next one last. A new change clears what can be redone.
+pub fn stacks(events: Vec<Event>) -> (Vec<Operation>, Vec<Operation>){
+    let mut undo: Vec<Operation> = Vec::new();
+    let mut redo: Vec<Operation> = Vec::new();
+
+    for operation in operations(events) {
+        match operation.action {
+            Action::Change      => {
+                undo.push(operation);
+                redo.clear();
+            }
+            Action::Undo(id)    => {
+                if let Some(index) = undo.iter().rposition(|done| done.id == id) {
+                    redo.push(undo.remove(index));
+                }
+            }
+            Action::Redo(id)    => {
+                if let Some(index) = redo.iter().rposition(|undone| undone.id == id) {
+                    undo.push(redo.remove(index));
+                }
+            }
+        }
+    }
+
+    (undo, redo)
+}
+
+/// Writes an event as a single line of JSON.
+pub fn encode(event: &Event) -> Result<String>{
+    let stored = Stored{
+        version:    schema::VERSION,
+        at:         event.at,
+        actor:      event.actor.clone(),
+        op:         Some(event.op),
+        action:     event.action,
+        before:     event.before.as_ref().map(serde_json::to_value).transpose()?,
+        after:      event.after.as_ref().map(serde_json::to_value).transpose()?,
+    };
+
+    Ok(serde_json::to_string(&stored)?)
+}
+
+pub fn decode(line: &str) -> Result<Event>{
+    let stored: Stored = serde_json::from_str(line)?;
+    let snapshot = |task: Option<Value>| -> Result<Option<Task>> {
+        let Some(task) = task else {
+            return Ok(None);
+        };
+        let mut tasks = [task];
+        schema::migrate(stored.version, &mut tasks);
+        let [task] = tasks;
+        Ok(Some(serde_json::from_value(task)?))
+    };
+
+    Ok(Event{
+        at:     stored.at,
+        actor:  stored.actor,
+        op:     stored.op.unwrap_or_else(Uuid::new_v4),
+        action: stored.action,
+        before: snapshot(stored.before)?,
+        after:  snapshot(stored.after)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{
+        Priority,
+        Status
+    };
+
+    fn task(id: i32, title: &str) -> Task{
+        let mut task = Task::new(Priority::Low, title.to_string(), String::new(), "2026-10-20".parse().unwrap(), Vec::new());
+        task.id = id;
+        task
+    }
+
+    fn event(op: Uuid, action: Action, before: Option<&Task>, after: Option<&Task>) -> Event{
+        Event::new("alice", op, action, before.cloned(), after.cloned())
+    }
+
+    fn ids(operations: &[Operation]) -> Vec<Uuid>{
+        operations.iter().map(|operation| operation.id).collect()
+    }
+
+    #[test]
+    fn undo_and_redo_move_operations_between_the_stacks(){
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let first = task(1, "First");
+        let second = task(2, "Second");
+        let mut events = vec![
+            event(a, Action::Change, None, Some(&first)),
+            event(b, Action::Change, None, Some(&second)),
+        ];
+
+        let (undo, redo) = stacks(events.clone());
+        assert_eq!((ids(&undo), ids(&redo)), (vec![a, b], vec![]));
+
+        events.push(event(Uuid::new_v4(), Action::Undo(b), Some(&second), None));
+        let (undo, redo) = stacks(events.clone());
+        assert_eq!((ids(&undo), ids(&redo)), (vec![a], vec![b]));
+
+        events.push(event(Uuid::new_v4(), Action::Undo(a), Some(&first), None));
+        let (undo, redo) = stacks(events.clone());
+        assert_eq!((ids(&undo), ids(&redo)), (vec![], vec![b, a]));
+
+        events.push(event(Uuid::new_v4(), Action::Redo(a), None, Some(&first)));
+        let (undo, redo) = stacks(events.clone());
+        assert_eq!((ids(&undo), ids(&redo)), (vec![a], vec![b]));
+    }
+
+    #[test]
+    fn a_new_change_clears_what_can_be_redone(){
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let first = task(1, "First");
+        let events = vec![
+            event(a, Action::Change, None, Some(&first)),
+            event(b, Action::Change, Some(&first), Some(&task(1, "Renamed"))),
+            event(Uuid::new_v4(), Action::Undo(b), Some(&task(1, "Renamed")), Some(&first)),
+            event(c, Action::Change, None, Some(&task(2, "Second"))),
+        ];
+
+        let (undo, redo) = stacks(events);
+        assert_eq!((ids(&undo), ids(&redo)), (vec![a, c], vec![]));
+    }
+
+    #[test]
+    fn an_operation_spans_its_first_and_last_change_to_each_task(){
+        let op = Uuid::new_v4();
+        let first = task(1, "First");
+        let mut renamed = first.clone();
+        renamed.title = "Renamed".to_string();
+        let mut done = renamed.clone();
+        done.status = Status::Completed;
+        let operation = Operation{ id: op, action: Action::Change, events: vec![
+            event(op, Action::Change, Some(&first), Some(&renamed)),
+            event(op, Action::Change, Some(&renamed), Some(&done)),
+        ] };
+
+        let tasks = operation.tasks();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].0.as_ref().map(|t| t.title.as_str()), Some("First"));
+        assert_eq!(tasks[0].1.as_ref().map(|t| t.status.clone()), Some(Status::Completed));
+        assert_eq!(operation.entries().iter().map(|entry| entry.field.as_str()).collect::<Vec<_>>(), ["title", "status"]);
+    }
+
+    #[test]
+    fn archiving_and_unarchiving_are_told_apart_from_removal(){
+        let op = Uuid::new_v4();
+        let mut archived = task(1, "Old");
+        archived.archived_at = Some(Utc::now());
+        let removed = task(2, "Gone");
+
+        let operation = Operation{ id: op, action: Action::Change, events: vec![
+            event(op, Action::Change, Some(&archived), None),
+            event(op, Action::Change, Some(&removed), None),
+        ] };
+        assert_eq!(operation.archived(), [archived.uuid]);
+        assert!(operation.unarchived().is_empty());
+
+        let operation = Operation{ id: op, action: Action::Change, events: vec![event(op, Action::Change, None, Some(&archived))] };
+        assert_eq!(operation.unarchived(), [archived.uuid]);
+        assert_eq!(operation.entries()[0].field, "unarchived");
+    }
+
+    #[test]
+    fn events_round_trip_and_old_events_are_upgraded(){
+        let first = task(3, "First");
+        let op = Uuid::new_v4();
+        let decoded = decode(&encode(&event(op, Action::Undo(op), Some(&first), None)).unwrap()).unwrap();
+        assert_eq!(decoded.op, op);
+        assert_eq!(decoded.action, Action::Undo(op));
+        assert_eq!(decoded.before.map(|t| t.uuid), Some(first.uuid));
+
+        // Written at version 9, before operations and projects.
+        let line = r#"{"version": 9, "at": "2026-10-01T09:00:00Z", "actor": "bob", "before": null, "after":
+            {"id": 5, "uuid": "8c4f4a2e-54a8-4f7e-9f0b-0f3d8f1b2a05", "priority": "Low", "title": "Old", "description": "",
+             "due_date": "2026-10-22", "due_time": null, "status": "NotCompleted", "assignees": ["bob"], "parent": null,
+             "blocked_by": [], "tags": [], "recurrence": null, "series": null, "status_history": [], "worklog": [], "estimate": null}}"#;
+        let decoded = decode(line).unwrap();
+        assert_eq!(decoded.action, Action::Change);
+        assert_eq!(decoded.after.map(|t| t.project).as_deref(), Some(crate::project::DEFAULT));
+    }
+}
@@ -0,0 +1,483 @@
+use chrono::NaiveDate;
+use owo_colors::OwoColorize;
+use std::{
+    thread,
+    time::Duration
+};
+
+use crate::{
+    app::App,
+    commands::{
+        self,
+        Target
+    },
+    error::{
+        Error,
+        Result
+    },
+    search::{
+        self,
+        Mode
+    },
+    estimate,
+    query::Query,
+    recur,
+    prompt::{
+        read_input,
+        prompt_user
+    },
+    history::Action,
+    task::{
+        self,
+        Task,
+        Field,
+        Status
+    },
+    view
+};
+
+const LOGO: &str = r#"
+░        ░░        ░░       ░░░  ░░░░  ░░        ░░░      ░░░░      ░░░  ░░░░  ░░        ░░       ░░
+▒▒▒▒  ▒▒▒▒▒  ▒▒▒▒▒▒▒▒  ▒▒▒▒  ▒▒   ▒▒   ▒▒▒▒▒  ▒▒▒▒▒  ▒▒▒▒  ▒▒  ▒▒▒▒▒▒▒▒  ▒▒▒  ▒▒▒  ▒▒▒▒▒▒▒▒  ▒▒▒▒  ▒
+▓▓▓▓  ▓▓▓▓▓      ▓▓▓▓       ▓▓▓        ▓▓▓▓▓  ▓▓▓▓▓  ▓▓▓▓  ▓▓▓      ▓▓▓     ▓▓▓▓▓      ▓▓▓▓       ▓▓
+████  █████  ████████  ███  ███  █  █  █████  █████        ████████  ██  ███  ███  ████████  ███  ██
+████  █████        ██  ████  ██  ████  █████  █████  ████  ███      ███  ████  ██        ██  ████  █
+"#;
+
+const DUE_DATE: &str = "Due Date (YYYY-MM-DD, or e.g. today, tomorrow, fri, next monday, +3d, in 2 weeks, end of month): ";
+
+/// Shows the date a due date expression resolved to and asks whether it is the right one.
+fn confirm_date(date: NaiveDate) -> bool{
+    let answer = prompt_user(&format!("Due {}, is that right? (Y/n): ", date.format("%A %Y-%m-%d").bold()));
+    !answer.to_lowercase().starts_with('n')
+}
+
+fn prompt_id(app: &mut App, message: &str) -> Option<i32>{
+    match task::parse_key(&prompt_user(message)).and_then(|key| commands::resolve_key(app, &key)) {
+        Ok(id)      => Some(id),
+        Err(err)    => {
+            println!("{}", err);
+            None
+        }
+    }
+}
+
+fn include_archived() -> bool{
+    prompt_user("Include archived tasks? (y/N): ").eq_ignore_ascii_case("y")
+}
+
+fn add_task(app: &mut App) -> Result<()>{
+    println!("Enter task details");
+    let task_prio = loop {
+        match task::parse_priority(&prompt_user("Priority (Critical, High, Medium, Low): ")) {
+            Ok(priority)    => break priority,
+            Err(err)        => println!("{}", err),
+        }
+    };
+    let task_title = loop {
+        match task::parse_title(&prompt_user("Title: ")) {
+            Ok(title)   => break title,
+            Err(err)    => println!("{}", err),
+        }
+    };
+    let task_desc = prompt_user("Description: ");
+    let task_due_date = loop {
+        match task::parse_due_date(&prompt_user(DUE_DATE)) {
+            Ok(date) if confirm_date(date)  => break date,
+            Ok(_)                           => continue,
+            Err(err)                        => println!("{}", err),
+        }
+    };
+
+    let assignees = loop {
+        match task::parse_assignees(&prompt_user("Assignees (separated by commas, at least 1): ")) {
+            Ok(assignees)   => break assignees,
+            Err(err)        => println!("{}", err),
+        }
+    };
+
+    let mut ntask = Task::new(task_prio, task_title, task_desc, task_due_date, assignees);
+    ntask.project = app.project.clone();
+    loop {
+        match ntask.set(Field::DueTime, &prompt_user("Due Time (HH:MM, optionally followed by a time zone such as Europe/Berlin, leave empty for any time that day): ")) {
+            Ok(())      => break,
+            Err(err)    => println!("{}", err),
+        }
+    }
+    ntask.recurrence = loop {
+        match recur::parse(&prompt_user(&format!("Repeat ({}, leave empty for a one-off task): ", recur::RULES))) {
+            Ok(recurrence)  => break recurrence,
+            Err(err)        => println!("{}", err),
+        }
+    };
+    ntask.estimate = loop {
+        match estimate::parse(&prompt_user("Estimate (e.g. 4h, 1h30m or 3pt, leave empty for none): ")) {
+            Ok(estimate)    => break estimate,
+            Err(err)        => println!("{}", err),
+        }
+    };
+    ntask.tags = loop {
+        match task::parse_tags(&prompt_user("Tags (separated by commas, leave empty for none): ")) {
+            Ok(tags)    => break tags,
+            Err(err)    => println!("{}", err),
+        }
+    };
+    ntask.parent = loop {
+        match commands::resolve_parent(app, &prompt_user("Parent task ID (leave empty for a top-level task): ")) {
+            Ok(parent)  => break parent,
+            Err(err)    => println!("{}", err),
+        }
+    };
+
+    let project = ntask.project.clone();
+    let id = commands::add(app, ntask)?;
+    println!("Task added with ID: {}", app.config.project_key(&project, id));
+    Ok(())
+}
+
+fn list_tasks(app: &mut App) -> Result<()>{
+    let query = loop {
+        match Query::parse(&prompt_user("Filter (e.g. priority:High status:open, leave empty to list every task): ")) {
+            Ok(query)   => break query,
+            Err(err)    => println!("{}", err),
+        }
+    };
+
+    let archived = include_archived();
+    let options = app.config.list.clone();
+    commands::list(app, &query, &options, archived)
+}
+
+fn change_status(app: &mut App) -> Result<()>{
+    let Some(init_id) = prompt_id(app, "Enter the ID of the task whose status you wish to change: ") else {
+        return Ok(());
+    };
+
+    let task = commands::find(app, init_id)?;
+    let allowed = app.config.statuses.allowed(&task.status);
+    if allowed.is_empty() {
+        println!("Task {} is {} and its status cannot be changed", app.config.key(&task), task.status);
+        return Ok(());
+    }
+
+    let names: Vec<&str> = allowed.iter().map(|status| status.name()).collect();
+    println!("Task {} is {}", app.config.key(&task), task.status);
+    // Completing is the most common change, so it is the default where it is allowed.
+    let default = allowed.contains(&Status::Completed);
+    let status = loop {
+        let answer = prompt_user(&format!("Enter the new status ({}){}: ", names.join(", "), if default { " [Completed]" } else { "" }));
+        let answer = if answer.is_empty() && default { "Completed".to_string() } else { answer };
+        match app.config.statuses.parse(&answer).and_then(|status| app.config.statuses.check(&task.status, &status).map(|_| status)) {
+            Ok(status)  => break status,
+            Err(err)    => println!("{}", err),
+        }
+    };
+
+    let change = commands::change_status(app, &Target::Id(init_id), status)?;
+    commands::print_status_change(app, &change);
+    Ok(())
+}
+
+fn print_removed_subtasks(removed: usize){
+    if removed > 1 {
+        println!("{} subtasks were moved to the trash with it", removed - 1);
+    }
+}
+
+fn remove_task(app: &mut App) -> Result<()>{
+    let Some(init_id) = prompt_id(app, "Enter the ID of the task you wish to remove: ") else {
+        return Ok(());
+    };
+
+    match commands::remove(app, init_id, None) {
+        Err(Error::Ambiguous(_, uuids)) => {
+            println!("Multiple tasks found with ID: {}", init_id);
+            println!("Please select the number corresponding to the UUID of the task you wish to remove:");
+
+            for (index, uuid) in uuids.iter().enumerate() {
+                println!("{}. {}", index + 1, uuid);
+            }
+
+            println!("Enter the number corresponding to the UUID to delete: ");
+            match read_input().parse::<usize>() {
+                Ok(selected_index) if selected_index > 0 && selected_index <= uuids.len() => {
+                    let uuid_to_remove = &uuids[selected_index - 1];
+                    let removed = commands::remove(app, init_id, Some(uuid_to_remove))?;
+                    println!("Task with UUID: {} moved to the trash", uuid_to_remove);
+                    print_removed_subtasks(removed);
+                }
+                _ => println!("Invalid selection."),
+            }
+            Ok(())
+        }
+        Err(err) => Err(err),
+        Ok(removed) => {
+            println!("Task with ID: {} moved to the trash", init_id);
+            print_removed_subtasks(removed);
+            Ok(())
+        }
+    }
+}
+
+fn edit_task(app: &mut App) -> Result<()>{
+    let Some(init_id) = prompt_id(app, "Enter the ID of the task you wish to edit: ") else {
+        return Ok(());
+    };
+
+    let mut task = commands::find(app, init_id)?;
+
+    let field = loop {
+        match prompt_user(&format!("Enter the field you wish to edit ({}): ", Field::NAMES)).parse::<Field>() {
+            Ok(field)   => break field,
+            Err(err)    => println!("{}", err),
+        }
+    };
+
+    let message = match field {
+        Field::Id           => "Enter the new ID: ",
+        Field::Priority     => "Enter the new priority (Critical, High, Medium, Low): ",
+        Field::Title        => "Enter the new title: ",
+        Field::Description  => "Enter the new description: ",
+        Field::DueDate      => DUE_DATE,
+        Field::DueTime      => "Enter the new due time (HH:MM, optionally followed by a time zone, none for any time that day): ",
+        Field::Status       => "Enter the new status: ",
+        Field::Assignees    => "Enter the new assignees (separated by commas): ",
+        Field::Parent       => "Enter the ID of the new parent task (none for a top-level task): ",
+        Field::Recurrence   => "Enter how the task repeats (daily, weekly:mon,fri, monthly:15, after:10, or none): ",
+        Field::Estimate     => "Enter the new estimate (e.g. 4h, 1h30m or 3pt, none to clear): ",
+        Field::Tags         => "Enter the new tags (separated by commas, none to clear): ",
+        Field::BlockedBy    => "Enter the IDs of the tasks that block it (separated by commas, none to unblock): ",
+    };
+
+    let mut status = None;
+    loop {
+        let value = prompt_user(message);
+        let result = match field {
+            // Parents and blockers are entered by ID, which only the store can turn into a UUID.
+            Field::Id           => task.set(field, &value).and_then(|_| commands::check_id_free(app, &task)),
+            Field::Parent       => commands::resolve_parent(app, &value).map(|parent| task.parent = parent),
+            Field::Status       => app.config.statuses.parse(&value)
+                .and_then(|new| app.config.statuses.check(&task.status, &new).map(|_| status = Some(new))),
+            Field::DueDate      => match task::parse_due_date(&value) {
+                Ok(date) if !confirm_date(date) => continue,
+                parsed                          => parsed.and_then(|date| task.set(field, &date.to_string())),
+            },
+            Field::BlockedBy    => commands::resolve_blockers(app, &value)
+                .and_then(|blockers| task.set(field, &blockers.iter().map(|b| b.to_string()).collect::<Vec<_>>().join(","))),
+            _                   => task.set(field, &value),
+        };
+        match result {
+            Ok(())      => break,
+            Err(err)    => println!("{}", err),
+        }
+    }
+
+    // Same as menu option 3, so closing a task applies the same rules.
+    if let Some(status) = status {
+        let change = commands::change_status(app, &Target::Id(init_id), status)?;
+        commands::print_status_change(app, &change);
+        return Ok(());
+    }
+    commands::update(app, &task)
+}
+
+fn search_tasks(app: &mut App) -> Result<()>{
+    let field = loop {
+        let answer = prompt_user(&format!("Enter the field you wish to search by ({}), or text to search titles, descriptions and assignees: ", Field::NAMES));
+        if answer == "text" {
+            break None;
+        }
+        match answer.parse::<Field>() {
+            Ok(field)   => break Some(field),
+            Err(err)    => println!("{}", err),
+        }
+    };
+
+    let Some(field) = field else {
+        let mode = loop {
+            match prompt_user("Match mode (substring, regex, fuzzy) [substring]: ").as_str() {
+                "" | "substring"    => break Mode::Substring,
+                "regex"             => break Mode::Regex,
+                "fuzzy"             => break Mode::Fuzzy,
+                other               => println!("Invalid match mode: {}", other),
+            }
+        };
+        let filter = loop {
+            match Query::parse(&prompt_user("Only search tasks matching (e.g. tag:backend, leave empty to search every task): ")) {
+                Ok(filter)  => break filter,
+                Err(err)    => println!("{}", err),
+            }
+        };
+        let query = prompt_user("Enter the text you wish to search for: ");
+        let archived = include_archived();
+
+        let hits = commands::find_text(app, &query, mode, &filter, archived)?;
+        search::print_hits(&app.config, &query, &hits);
+        return Ok(());
+    };
+    let value = prompt_user("Enter the value you wish to search for: ");
+    let archived = include_archived();
+
+    let matching_tasks = commands::search(app, field, &value, archived)?;
+    commands::print_search_results(app, field, &value, matching_tasks)
+}
+
+fn view_task(app: &mut App) -> Result<()>{
+    let Some(id) = prompt_id(app, "Enter the ID of the task you wish to view: ") else {
+        return Ok(());
+    };
+
+    commands::show(app, id)?;
+    let text = prompt_user("Add a comment (leave empty to go back): ");
+    if !text.is_empty() {
+        let author = commands::comment(app, id, &text, None)?;
+        println!("{} commented on task {}", author, commands::key(app, id));
+    }
+
+    Ok(())
+}
+
+fn undo_or_redo(app: &mut App) -> Result<()>{
+    let undo = commands::undo(app, false).ok();
+    let redo = commands::redo(app, false).ok();
+    if let Some(operation) = &undo {
+        view::print_operation(&app.config, operation, "u: Undo");
+    }
+    if let Some(operation) = &redo {
+        view::print_operation(&app.config, operation, "r: Redo");
+    }
+    if undo.is_none() && redo.is_none() {
+        println!("Nothing to undo or redo");
+        return Ok(());
+    }
+
+    match prompt_user("Enter u to undo or r to redo (leave empty to go back): ").to_lowercase().as_str() {
+        "u" if undo.is_some()   => {
+            commands::undo(app, true)?;
+            println!("Change undone");
+        }
+        "r" if redo.is_some()   => {
+            commands::redo(app, true)?;
+            println!("Change redone");
+        }
+        ""                      => {}
+        _                       => println!("Nothing was changed"),
+    }
+
+    Ok(())
+}
+
+fn manage_trash(app: &mut App) -> Result<()>{
+    let tasks = commands::trash(app)?;
+    view::print_trash(&app.config, &tasks);
+    if tasks.is_empty() {
+        return Ok(());
+    }
+
+    match prompt_user("Enter r to restore or p to purge a task (leave empty to go back): ").to_lowercase().as_str() {
+        "r" => {
+            let Some(id) = prompt_id(app, "Enter the ID of the task you wish to restore: ") else {
+                return Ok(());
+            };
+            let task = commands::restore(app, id, None)?;
+            println!("Task with ID: {} restored as task {}", id, app.config.key(&task));
+        }
+        "p" => {
+            let Some(id) = prompt_id(app, "Enter the ID of the task you wish to purge for good: ") else {
+                return Ok(());
+            };
+            commands::purge(app, Some(id), None)?;
+            println!("Task with ID: {} purged", id);
+        }
+        _   => {}
+    }
+
+    Ok(())
+}
+
+fn archive_tasks(app: &mut App) -> Result<()>{
+    match prompt_user("Enter the ID of a completed or cancelled task to archive, or leave empty to archive every one: ").as_str() {
+        ""  => {
+            let archived = commands::archive(app, None)?;
+            println!("Archived {} tasks", archived);
+        }
+        id  => {
+            let id = commands::resolve_key(app, &task::parse_key(id)?)?;
+            commands::archive(app, Some(&Target::Id(id)))?;
+            println!("Task with ID: {} archived", id);
+        }
+    }
+
+    Ok(())
+}
+
+fn switch_project(app: &mut App) -> Result<()>{
+    for project in commands::projects(app)? {
+        let marker = if project.name == app.project { "*" } else { " " };
+        println!("{} {}  {} open, {} total", marker, project.name, project.open, project.total);
+    }
+
+    let name = prompt_user("Enter the project to switch to, or a new name to start one (leave empty to go back): ");
+    if !name.is_empty() {
+        app.switch_project(&name)?;
+        println!("Switched to project {}", app.project);
+    }
+
+    Ok(())
+}
+
+/// Runs the interactive numbered menu until the user chooses to exit.
+pub fn run(app: &mut App){
+    //clear_screen();
+    println!("{}", LOGO.bold().truecolor(176,0,0));
+    println!("Project: {}", app.project.bold());
+    println!("1. Add Task");
+    println!("2. List Tasks");
+    println!("3. Change task status");
+    println!("4. Remove task");
+    println!("5. Edit a task");
+    println!("6. Search for a task");
+    println!("7. View and comment on a task");
+    println!("8. Undo or redo a change");
+    println!("9. Trash");
+    println!("10. Archive closed tasks");
+    println!("11. Switch project");
+    println!("12. Exit");
+    println!();
+    loop {
+        thread::sleep(Duration::from_secs(1));
+        let choice: u32 = match prompt_user("Choose an option: ").parse(){
+            Ok(num) => num,
+            Err(_) => continue,
+        };
+        // Each option is undone as a whole.
+        app.begin_operation(Action::Change);
+        let result = match choice{
+            1 => add_task(app),
+            2 => list_tasks(app),
+            3 => change_status(app),
+            4 => remove_task(app),
+            5 => edit_task(app),
+            6 => search_tasks(app),
+            7 => view_task(app),
+            8 => undo_or_redo(app),
+            9 => manage_trash(app),
+            10 => archive_tasks(app),
+            11 => switch_project(app),
+            12 => {
+                println!("{}",r#"Exiting..."#.bold().red());
+                let duration = Duration::from_secs(1);
+                thread::sleep(duration);
+                break;
+            }
+            _ => {
+                println!("Invalid option, please choose a number between 1&12.");
+                Ok(())
+            }
+        };
+
+        if let Err(err) = result {
+            eprintln!("{}", err);
+        }
+    }
+}
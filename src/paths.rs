@@ -0,0 +1,140 @@
+use std::{
+    env,
+    fs,
+    io,
+    path::{
+        Path,
+        PathBuf
+    }
+};
+
+use crate::error::{
+    Error,
+    Result
+};
+
+/// Files that make up a data directory, in the order they are migrated.
+const DATA_FILES: [&str; 3] = ["tasks.json", "tasks.db", "config.json"];
+
+/// Where older releases kept their data: a `db` directory next to the executable.
+fn legacy_dir() -> Option<PathBuf>{
+    let exe_path = env::current_exe().ok()?;
+    Some(exe_path.parent()?.join("db"))
+}
+
+/// The platform data directory, e.g. `$XDG_DATA_HOME/termtasker` on Linux.
+fn default_dir() -> Result<PathBuf>{
+    dirs::data_dir()
+        .map(|dir| dir.join("termtasker"))
+        .ok_or_else(|| Error::Invalid("Unable to determine the data directory, pass --db <path> or set TERMTASKER_DB".to_string()))
+}
+
+fn has_data(dir: &Path) -> bool{
+    DATA_FILES.iter().any(|name| dir.join(name).exists())
+}
+
+fn move_file(from: &Path, to: &Path) -> io::Result<()>{
+    // A plain rename fails across filesystems, e.g. from /usr/local/bin to $HOME.
+    if fs::rename(from, to).is_err() {
+        fs::copy(from, to)?;
+        fs::remove_file(from)?;
+    }
+
+    Ok(())
+}
+
+/// Moves the data an older release left in `legacy` into `dir`, unless `dir`
+/// already holds data.
+fn migrate_legacy(legacy: &Path, dir: &Path) -> Result<()>{
+    if legacy == dir || !has_data(legacy) || has_data(dir) {
+        return Ok(());
+    }
+
+    for name in DATA_FILES {
+        let from = legacy.join(name);
+        if from.exists() {
+            move_file(&from, &dir.join(name))?;
+        }
+    }
+    let _ = fs::remove_file(legacy.join("tasks.json.lock"));
+    let _ = fs::remove_dir(legacy);
+
+    eprintln!("Moved existing tasks from {} to {}", legacy.display(), dir.display());
+
+    Ok(())
+}
+
+/// Resolves and creates the data directory. `db` comes from `--db` or
+/// `TERMTASKER_DB`; without it the platform data directory is used.
+pub fn data_dir(db: Option<PathBuf>) -> Result<PathBuf>{
+    let dir = match db {
+        Some(dir)   => dir,
+        None        => default_dir()?,
+    };
+
+    fs::create_dir_all(&dir)?;
+    if let Some(legacy) = legacy_dir() {
+        migrate_legacy(&legacy, &dir)?;
+    }
+
+    Ok(dir)
+}
+
+/// An empty directory under the system's temporary directory for tests that
+/// need files, removed with everything in it when dropped.
+#[cfg(test)]
+pub struct TempDir{
+    pub path: PathBuf,
+}
+
+#[cfg(test)]
+impl TempDir{
+    pub fn new() -> TempDir{
+        let path = env::temp_dir().join(format!("termtasker-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&path).unwrap();
+
+        TempDir{ path }
+    }
+}
+
+#[cfg(test)]
+impl Drop for TempDir{
+    fn drop(&mut self){
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_data_moves_once(){
+        let temp = TempDir::new();
+        let (legacy, dir) = (temp.path.join("db"), temp.path.join("data"));
+        fs::create_dir_all(&legacy).unwrap();
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(legacy.join("tasks.json"), "[]").unwrap();
+        fs::write(legacy.join("config.json"), "{}").unwrap();
+        fs::write(legacy.join("tasks.json.lock"), "").unwrap();
+
+        migrate_legacy(&legacy, &dir).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("tasks.json")).unwrap(), "[]");
+        assert!(dir.join("config.json").exists());
+        assert!(!legacy.exists());
+    }
+
+    #[test]
+    fn existing_data_is_never_overwritten(){
+        let temp = TempDir::new();
+        let (legacy, dir) = (temp.path.join("db"), temp.path.join("data"));
+        fs::create_dir_all(&legacy).unwrap();
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(legacy.join("tasks.json"), "old").unwrap();
+        fs::write(dir.join("tasks.db"), "new").unwrap();
+
+        migrate_legacy(&legacy, &dir).unwrap();
+        assert_eq!(fs::read_to_string(legacy.join("tasks.json")).unwrap(), "old");
+        assert!(!dir.join("tasks.json").exists());
+    }
+}
@@ -0,0 +1,45 @@
+use std::{
+    fs,
+    io::ErrorKind,
+    path::Path
+};
+
+use crate::{
+    atomic,
+    error::{
+        Error,
+        Result
+    }
+};
+
+/// The project tasks belong to until they are moved, and the current project
+/// until another one is switched to.
+pub const DEFAULT: &str = "default";
+
+/// Remembers the current project inside the data directory.
+const CURRENT_FILE: &str = "project";
+
+/// Checks a project name. Names are single words so filter queries such as
+/// `project:web,api` can refer to them.
+pub fn parse(input: &str) -> Result<String>{
+    let name = input.trim();
+    if name.is_empty() || name.contains(|c: char| c.is_whitespace() || matches!(c, ',' | '(' | ')')) {
+        return Err(Error::Invalid(format!("Invalid project name: '{}', expected a single word such as web or client-a", input)));
+    }
+
+    Ok(name.to_string())
+}
+
+/// The project last switched to in `dir`, or the default project.
+pub fn current(dir: &Path) -> Result<String>{
+    match fs::read_to_string(dir.join(CURRENT_FILE)) {
+        Ok(name)                                        => parse(&name),
+        Err(err) if err.kind() == ErrorKind::NotFound   => Ok(DEFAULT.to_string()),
+        Err(err)                                        => Err(err.into()),
+    }
+}
+
+/// Makes `name` the current project in `dir`.
+pub fn switch(dir: &Path, name: &str) -> Result<()>{
+    Ok(atomic::write(&dir.join(CURRENT_FILE), name.as_bytes())?)
+}
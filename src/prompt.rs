@@ -0,0 +1,46 @@
+use std::{
+    io::{
+        self,
+        Write
+    },
+    process::{
+        self,
+        Command
+    }
+};
+
+pub fn read_input() -> String{
+    let mut input = String::new();
+    let read = io::stdin().read_line(&mut input).expect("Failed to read line.");
+
+    // Standard input was closed (Ctrl-D or the end of a piped script), so no
+    // further answer can ever arrive.
+    if read == 0 {
+        process::exit(0);
+    }
+
+    input.trim().to_string()
+}
+
+#[allow(dead_code)]
+pub fn clear_screen(){
+    let cmd_name = if cfg!(unix){
+        "clear"
+    } else if cfg!(windows){
+        "cls"
+    } else {
+        panic!("Unsupported platform");
+    };
+
+    let status = Command::new(cmd_name).status().expect("Failed to execute command");
+
+    if !status.success(){
+        eprintln!("command failed with exit code: {}",status);
+    }
+}
+
+pub fn prompt_user(message: &str) -> String {
+    println!("{}", message);
+    io::stdout().flush().unwrap();
+    read_input()
+}
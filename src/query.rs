@@ -0,0 +1,570 @@
+//! A small filter language over tasks, e.g.
+//! `priority:High,Critical status:open due<2026-11-01 assignee:alice -tag:blocked`.
+//!
+//! Terms are `field<op>value` conditions or bare words that must occur in the
+//! title, description, assignees or tags. Terms next to each other must all match;
+//! `or`, `and`, `not` (or a leading `-`) and parentheses combine them.
+
+use chrono::{
+    Datelike,
+    NaiveDate
+};
+
+use crate::{
+    config::Config,
+    error::{
+        Error,
+        Result
+    },
+    task::{
+        self,
+        Key,
+        Task,
+        Status
+    }
+};
+
+pub const FIELDS: &str = "id, priority, status, title, description, due, assignee, tag, project";
+
+#[derive(Debug,Clone,Copy,PartialEq)]
+enum Op{
+    /// `:` or `=`, matches any of a comma separated list of values
+    Eq,
+    /// `!=`, matches none of a comma separated list of values
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op{
+    fn symbol(&self) -> &'static str{
+        match self {
+            Op::Eq  => "=",
+            Op::Ne  => "!=",
+            Op::Lt  => "<",
+            Op::Le  => "<=",
+            Op::Gt  => ">",
+            Op::Ge  => ">=",
+        }
+    }
+
+    fn holds(&self, actual: i64, expected: i64) -> bool{
+        match self {
+            Op::Eq  => actual == expected,
+            Op::Ne  => actual != expected,
+            Op::Lt  => actual < expected,
+            Op::Le  => actual <= expected,
+            Op::Gt  => actual > expected,
+            Op::Ge  => actual >= expected,
+        }
+    }
+}
+
+/// Fields compared as numbers: priority levels and due dates as day numbers.
+#[derive(Debug,Clone,Copy)]
+enum Ordered{
+    Priority,
+    Due,
+}
+
+#[derive(Debug,Clone,Copy)]
+enum Text{
+    Title,
+    Description,
+}
+
+#[derive(Debug,Clone)]
+enum StatusValue{
+    Is(Status),
+    /// `open`: any status but Completed and Cancelled
+    Open,
+    /// `closed`: Completed or Cancelled
+    Closed,
+}
+
+impl StatusValue{
+    fn matches(&self, status: &Status) -> bool{
+        match self {
+            StatusValue::Is(expected)   => expected.name().eq_ignore_ascii_case(status.name()),
+            StatusValue::Open           => status.is_open(),
+            StatusValue::Closed         => !status.is_open(),
+        }
+    }
+}
+
+#[derive(Debug,Clone)]
+enum Cond{
+    /// IDs compare as numbers. One given with a prefix only matches tasks of
+    /// the projects whose IDs are shown with it.
+    Id(Op, Vec<Key>),
+    Ordered(Ordered, Op, Vec<i64>),
+    Status(Op, Vec<StatusValue>),
+    /// `:` is a substring match, `=` and `!=` compare the whole text. Both ignore case.
+    Text(Text, Op, bool, Vec<String>),
+    Assignee(Op, Vec<String>),
+    Tag(Op, Vec<String>),
+    Project(Op, Vec<String>),
+    /// A bare word, matched anywhere in the title, description, assignees or tags.
+    Anywhere(String),
+}
+
+#[derive(Debug,Clone)]
+enum Expr{
+    All,
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Cond(Cond),
+}
+
+/// A parsed filter. The empty query matches every task.
+#[derive(Debug,Clone)]
+pub struct Query{
+    expr: Expr,
+}
+
+fn contains(haystack: &str, needle: &str) -> bool{
+    haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+impl Cond{
+    fn matches(&self, config: &Config, task: &Task) -> bool{
+        match self {
+            Cond::Id(op, keys) => {
+                let prefix = config.prefix(&task.project);
+                let mut keys = keys.iter().filter(|key| {
+                    key.prefix.as_deref().is_none_or(|given| prefix.is_some_and(|prefix| prefix.eq_ignore_ascii_case(given)))
+                });
+                match op {
+                    Op::Eq  => keys.any(|key| key.id == task.id),
+                    Op::Ne  => !keys.any(|key| key.id == task.id),
+                    _       => keys.any(|key| op.holds(i64::from(task.id), i64::from(key.id))),
+                }
+            }
+            Cond::Ordered(field, op, values) => {
+                let actual = match field {
+                    Ordered::Priority   => i64::from(task.priority.level()),
+                    Ordered::Due        => i64::from(task.due_date.num_days_from_ce()),
+                };
+                match op {
+                    Op::Eq  => values.contains(&actual),
+                    Op::Ne  => !values.contains(&actual),
+                    _       => values.iter().all(|v| op.holds(actual, *v)),
+                }
+            }
+            Cond::Status(op, values) => {
+                let found = values.iter().any(|v| v.matches(&task.status));
+                if *op == Op::Ne { !found } else { found }
+            }
+            Cond::Text(field, op, substring, values) => {
+                let text = match field {
+                    Text::Title         => &task.title,
+                    Text::Description   => &task.description,
+                };
+                let found = values.iter().any(|v| if *substring { contains(text, v) } else { text.to_lowercase() == v.to_lowercase() });
+                if *op == Op::Ne { !found } else { found }
+            }
+            Cond::Assignee(op, values) => {
+                let found = task.assignees.iter().any(|a| values.iter().any(|v| a.to_lowercase() == v.to_lowercase()));
+                if *op == Op::Ne { !found } else { found }
+            }
+            Cond::Tag(op, values) => {
+                let found = values.iter().any(|v| task.tags.contains(v));
+                if *op == Op::Ne { !found } else { found }
+            }
+            Cond::Project(op, values) => {
+                let found = values.iter().any(|v| task.project.eq_ignore_ascii_case(v));
+                if *op == Op::Ne { !found } else { found }
+            }
+            Cond::Anywhere(word) => {
+                contains(&task.title, word)
+                    || contains(&task.description, word)
+                    || task.assignees.iter().any(|a| contains(a, word))
+                    || task.tags.iter().any(|t| contains(t, word))
+            }
+        }
+    }
+}
+
+impl Expr{
+    fn matches(&self, config: &Config, task: &Task) -> bool{
+        match self {
+            Expr::All               => true,
+            Expr::And(left, right)  => left.matches(config, task) && right.matches(config, task),
+            Expr::Or(left, right)   => left.matches(config, task) || right.matches(config, task),
+            Expr::Not(inner)        => !inner.matches(config, task),
+            Expr::Cond(cond)        => cond.matches(config, task),
+        }
+    }
+}
+
+impl Query{
+    /// The query that matches every task.
+    pub fn all() -> Query{
+        Query{ expr: Expr::All }
+    }
+
+    pub fn parse(input: &str) -> Result<Query>{
+        let mut parser = Parser{ input, pos: 0 };
+
+        parser.skip_whitespace();
+        if parser.at_end() {
+            return Ok(Query::all());
+        }
+
+        let expr = parser.parse_or()?;
+        parser.skip_whitespace();
+        if !parser.at_end() {
+            return Err(parser.error(parser.pos, "unexpected ')'"));
+        }
+
+        Ok(Query{ expr })
+    }
+
+    /// Whether `task` matches. `config` tells which prefixes its ID is shown with.
+    pub fn matches(&self, config: &Config, task: &Task) -> bool{
+        self.expr.matches(config, task)
+    }
+}
+
+struct Parser<'a>{
+    input:  &'a str,
+    pos:    usize,
+}
+
+impl Parser<'_>{
+    fn error(&self, pos: usize, message: &str) -> Error{
+        let column = self.input[..pos].chars().count();
+        Error::Invalid(format!("Invalid query: {}\n    {}\n    {}^", message, self.input, " ".repeat(column)))
+    }
+
+    fn rest(&self) -> &str{
+        &self.input[self.pos..]
+    }
+
+    fn at_end(&self) -> bool{
+        self.pos >= self.input.len()
+    }
+
+    fn peek(&self) -> Option<char>{
+        self.rest().chars().next()
+    }
+
+    fn skip_whitespace(&mut self){
+        let trimmed = self.rest().trim_start();
+        self.pos = self.input.len() - trimmed.len();
+    }
+
+    /// Whether `keyword` (in any case) comes next as a word of its own.
+    fn at_keyword(&self, keyword: &str) -> bool{
+        let rest = self.rest();
+        let boundary = rest.get(keyword.len()..).is_some_and(|after| {
+            after.chars().next().is_none_or(|c| c.is_whitespace() || c == '(' || c == ')')
+        });
+
+        boundary && rest[..keyword.len()].eq_ignore_ascii_case(keyword)
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool{
+        if self.at_keyword(keyword) {
+            self.pos += keyword.len();
+            return true;
+        }
+        false
+    }
+
+    fn parse_or(&mut self) -> Result<Expr>{
+        let mut left = self.parse_and()?;
+
+        loop {
+            self.skip_whitespace();
+            if !self.eat_keyword("or") {
+                return Ok(left);
+            }
+            let right = self.parse_and()?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+    }
+
+    fn parse_and(&mut self) -> Result<Expr>{
+        let mut left = self.parse_unary()?;
+
+        loop {
+            self.skip_whitespace();
+            if self.at_end() || self.peek() == Some(')') || self.at_keyword("or") {
+                return Ok(left);
+            }
+            self.eat_keyword("and");
+            let right = self.parse_unary()?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr>{
+        self.skip_whitespace();
+
+        if self.peek() == Some('-') || self.eat_keyword("not") {
+            if self.peek() == Some('-') {
+                self.pos += 1;
+            }
+            let inner = self.parse_unary()?;
+            return Ok(Expr::Not(Box::new(inner)));
+        }
+
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr>{
+        self.skip_whitespace();
+
+        match self.peek() {
+            None        => Err(self.error(self.pos, "expected a condition")),
+            Some(')')   => Err(self.error(self.pos, "expected a condition before ')'")),
+            Some('(')   => {
+                let open = self.pos;
+                self.pos += 1;
+                let inner = self.parse_or()?;
+                self.skip_whitespace();
+                if self.peek() != Some(')') {
+                    return Err(self.error(open, "this '(' is never closed"));
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            Some(_)     => Ok(Expr::Cond(self.parse_condition()?)),
+        }
+    }
+
+    /// Reads a `field<op>` prefix if there is one.
+    fn parse_field(&mut self) -> Option<(&str, Op)>{
+        let rest = self.rest();
+        let name_len = rest.find(|c: char| !(c.is_ascii_alphabetic() || c == '_')).unwrap_or(rest.len());
+        if name_len == 0 {
+            return None;
+        }
+
+        let (op, op_len) = match &rest[name_len..] {
+            after if after.starts_with("!=")    => (Op::Ne, 2),
+            after if after.starts_with("<=")    => (Op::Le, 2),
+            after if after.starts_with(">=")    => (Op::Ge, 2),
+            after if after.starts_with(':')     => (Op::Eq, 1),
+            after if after.starts_with('=')     => (Op::Eq, 1),
+            after if after.starts_with('<')     => (Op::Lt, 1),
+            after if after.starts_with('>')     => (Op::Gt, 1),
+            _                                   => return None,
+        };
+
+        let name = &self.input[self.pos..self.pos + name_len];
+        self.pos += name_len + op_len;
+        Some((name, op))
+    }
+
+    /// Reads a quoted string or a run of characters up to whitespace or a parenthesis.
+    fn parse_value(&mut self) -> Result<String>{
+        let start = self.pos;
+
+        if self.peek() == Some('"') {
+            let closing = self.rest()[1..].find('"').ok_or_else(|| self.error(start, "this '\"' is never closed"))?;
+            let value = self.rest()[1..1 + closing].to_string();
+            self.pos += closing + 2;
+            return Ok(value);
+        }
+
+        let len = self.rest().find(|c: char| c.is_whitespace() || c == '(' || c == ')').unwrap_or(self.rest().len());
+        self.pos += len;
+        Ok(self.input[start..self.pos].to_string())
+    }
+
+    fn parse_condition(&mut self) -> Result<Cond>{
+        let start = self.pos;
+
+        let Some((name, op)) = self.parse_field() else {
+            let word = self.parse_value()?;
+            if word.is_empty() {
+                return Err(self.error(start, "expected a condition"));
+            }
+            return Ok(Cond::Anywhere(word));
+        };
+        let name = name.to_lowercase();
+        let op_text = self.input[start..self.pos].to_string();
+
+        let value_start = self.pos;
+        let value = self.parse_value()?;
+        if value.is_empty() {
+            return Err(self.error(value_start, &format!("expected a value after '{}'", op_text)));
+        }
+
+        let values: Vec<&str> = value.split(',').map(str::trim).filter(|v| !v.is_empty()).collect();
+        let ordered = !matches!(op, Op::Eq | Op::Ne);
+        if ordered && values.len() > 1 {
+            return Err(self.error(value_start, &format!("'{}' takes a single value, not a list", op.symbol())));
+        }
+        let only_equality = |parser: &Parser| -> Result<()> {
+            if ordered {
+                return Err(parser.error(start, &format!("'{}' cannot be used with {}, use ':' or '!='", op.symbol(), name)));
+            }
+            Ok(())
+        };
+        let parse_each = |parser: &Parser, parse: &dyn Fn(&str) -> Result<i64>| -> Result<Vec<i64>> {
+            values.iter()
+                .map(|v| parse(v).map_err(|err| parser.error(value_start, &err.to_string())))
+                .collect()
+        };
+
+        match name.as_str() {
+            "id"                        => {
+                let keys = values.iter()
+                    .map(|v| task::parse_key(v).map_err(|err| self.error(value_start, &err.to_string())))
+                    .collect::<Result<_>>()?;
+                Ok(Cond::Id(op, keys))
+            }
+            "priority" | "prio"         => Ok(Cond::Ordered(Ordered::Priority, op, parse_each(self, &|v| Ok(i64::from(task::parse_priority(v)?.level())))?)),
+            "due" | "due_date"          => Ok(Cond::Ordered(Ordered::Due, op, parse_each(self, &|v| Ok(i64::from(parse_date(v)?.num_days_from_ce())))?)),
+            "status"                    => {
+                only_equality(self)?;
+                let statuses = values.iter()
+                    .map(|v| parse_status(v).map_err(|err| self.error(value_start, &err.to_string())))
+                    .collect::<Result<_>>()?;
+                Ok(Cond::Status(op, statuses))
+            }
+            "title" | "description" | "desc" => {
+                only_equality(self)?;
+                let field = if name == "title" { Text::Title } else { Text::Description };
+                let substring = op_text.ends_with(':');
+                Ok(Cond::Text(field, op, substring, values.iter().map(|v| v.to_string()).collect()))
+            }
+            "assignee" | "assignees"    => {
+                only_equality(self)?;
+                Ok(Cond::Assignee(op, values.iter().map(|v| v.to_string()).collect()))
+            }
+            "tag" | "tags"              => {
+                only_equality(self)?;
+                let tags = values.iter()
+                    .map(|v| task::parse_tag(v).map_err(|err| self.error(value_start, &err.to_string())))
+                    .collect::<Result<_>>()?;
+                Ok(Cond::Tag(op, tags))
+            }
+            "project"                   => {
+                only_equality(self)?;
+                Ok(Cond::Project(op, values.iter().map(|v| v.to_string()).collect()))
+            }
+            _                           => Err(self.error(start, &format!("unknown field '{}', expected one of: {}", name, FIELDS))),
+        }
+    }
+}
+
+fn parse_date(input: &str) -> Result<NaiveDate>{
+    task::parse_due_date(input)
+}
+
+/// Accepts `open`, `closed` and `done` (Completed) next to the status names.
+/// Custom status names are matched as they are written.
+fn parse_status(input: &str) -> Result<StatusValue>{
+    match input.to_lowercase().as_str() {
+        "open"      => Ok(StatusValue::Open),
+        "closed"    => Ok(StatusValue::Closed),
+        "done"      => Ok(StatusValue::Is(Status::Completed)),
+        _           => Ok(StatusValue::Is(task::parse_status(input)?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+
+    fn task(id: i32, priority: Priority, title: &str, status: Status, assignees: &[&str], tags: &[&str]) -> Task{
+        let mut task = Task::new(priority, title.to_string(), String::new(), "2026-10-20".parse().unwrap(), assignees.iter().map(|a| a.to_string()).collect());
+        task.id = id;
+        task.status = status;
+        task.tags = tags.iter().map(|t| t.to_string()).collect();
+        task
+    }
+
+    fn tasks() -> Vec<Task>{
+        vec![
+            task(1, Priority::Critical, "Deploy staging", Status::NotCompleted, &["alice"], &["backend"]),
+            task(2, Priority::High, "Write release notes", Status::Completed, &["bob"], &["docs"]),
+            task(3, Priority::Low, "Fix login draft", Status::InProgress, &["alice", "carol"], &["backend", "blocked"]),
+        ]
+    }
+
+    /// The IDs of the sample tasks `input` matches.
+    fn ids(input: &str) -> Vec<i32>{
+        let query = Query::parse(input).unwrap();
+        tasks().iter().filter(|t| query.matches(&Config::default(), t)).map(|t| t.id).collect()
+    }
+
+    #[test]
+    fn empty_query_matches_everything(){
+        assert_eq!(ids(""), [1, 2, 3]);
+        assert_eq!(ids("   "), [1, 2, 3]);
+    }
+
+    #[test]
+    fn field_terms(){
+        assert_eq!(ids("priority:High,Critical"), [1, 2]);
+        assert_eq!(ids("priority>=high"), [1, 2]);
+        assert_eq!(ids("status:open"), [1, 3]);
+        assert_eq!(ids("status:done"), [2]);
+        assert_eq!(ids("assignee:ALICE"), [1, 3]);
+        assert_eq!(ids("tag:backend"), [1, 3]);
+        assert_eq!(ids("title:deploy"), [1]);
+        assert_eq!(ids("id>1"), [2, 3]);
+        assert_eq!(ids("id!=2"), [1, 3]);
+        assert_eq!(ids("due<2026-10-21"), [1, 2, 3]);
+        assert_eq!(ids("project:default"), [1, 2, 3]);
+    }
+
+    #[test]
+    fn prefixed_ids_only_match_their_projects(){
+        let config: Config = serde_json::from_str(r#"{ "id_prefix": "OPS", "projects": { "web": { "id_prefix": "WEB" } } }"#).unwrap();
+        let mut tasks = tasks();
+        tasks[1].project = "web".to_string();
+        let ids = |input: &str| -> Vec<i32> {
+            let query = Query::parse(input).unwrap();
+            tasks.iter().filter(|t| query.matches(&config, t)).map(|t| t.id).collect()
+        };
+
+        assert_eq!(ids("id:2"), [2]);
+        assert_eq!(ids("id:WEB-2"), [2]);
+        assert_eq!(ids("id:ops-2"), Vec::<i32>::new());
+        assert_eq!(ids("id:OPS-1,web-2"), [1, 2]);
+        assert_eq!(ids("id!=OPS-2"), [1, 2, 3]);
+        assert_eq!(ids("id>=OPS-2"), [3]);
+        assert!(Query::parse("id:OPS-0").is_err());
+    }
+
+    #[test]
+    fn bare_words_match_anywhere(){
+        assert_eq!(ids("carol"), [3]);
+        assert_eq!(ids("release"), [2]);
+        assert_eq!(ids("docs"), [2]);
+    }
+
+    #[test]
+    fn combinations(){
+        assert_eq!(ids("assignee:alice status:open"), [1, 3]);
+        assert_eq!(ids("assignee:alice and priority:low"), [3]);
+        assert_eq!(ids("deploy or release"), [1, 2]);
+        assert_eq!(ids("-tag:blocked"), [1, 2]);
+        assert_eq!(ids("not tag:blocked"), [1, 2]);
+        assert_eq!(ids("-title:draft assignee:alice"), [1]);
+        // `and` binds tighter than `or`.
+        assert_eq!(ids("release or deploy assignee:bob"), [2]);
+        assert_eq!(ids("(release or deploy) assignee:alice"), [1]);
+    }
+
+    #[test]
+    fn invalid_queries_point_at_the_problem(){
+        for input in ["priority:", "priority:urgent", "color:red", "(deploy", "deploy)", "status>open", "id:0"] {
+            assert!(Query::parse(input).is_err(), "{} was accepted", input);
+        }
+        let Err(Error::Invalid(message)) = Query::parse("priority:") else {
+            panic!("expected an invalid query");
+        };
+        assert!(message.ends_with("priority:\n             ^"), "{}", message);
+    }
+}
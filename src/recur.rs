@@ -0,0 +1,183 @@
+use chrono::{
+    Datelike,
+    Days,
+    Months,
+    NaiveDate,
+    Weekday
+};
+use serde::{
+    Serialize,
+    Deserialize
+};
+use std::{
+    fmt,
+    str::FromStr
+};
+
+use crate::error::{
+    Error,
+    Result
+};
+
+pub const RULES: &str = "daily, weekly:mon,fri, monthly:15 or after:10";
+
+/// How a recurring task repeats. Written and parsed as `daily`,
+/// `weekly:mon,fri`, `monthly:15` or `after:10`.
+#[derive(Debug,Clone,PartialEq,Serialize,Deserialize)]
+#[serde(tag = "rule", rename_all = "lowercase")]
+pub enum Recurrence{
+    Daily,
+    /// On the given weekdays, or on the weekday of the due date when none are given.
+    Weekly{ weekdays: Vec<Weekday> },
+    /// On a day of the month, or the month's last day when it is shorter.
+    Monthly{ day: u32 },
+    /// A number of days after the previous occurrence was completed.
+    After{ days: u32 },
+}
+
+impl Recurrence{
+    /// The due date of the occurrence after one due on `due` and completed on `completed`.
+    pub fn next(&self, due: NaiveDate, completed: NaiveDate) -> NaiveDate{
+        match self {
+            Recurrence::Daily               => due + Days::new(1),
+            Recurrence::Weekly{ weekdays }  => {
+                (1..=7)
+                    .map(|offset| due + Days::new(offset))
+                    .find(|date| (weekdays.is_empty() && date.weekday() == due.weekday()) || weekdays.contains(&date.weekday()))
+                    .unwrap_or(due + Days::new(7))
+            }
+            Recurrence::Monthly{ day }      => {
+                let in_month = |month: NaiveDate| {
+                    let first = month.with_day(1).expect("every month has a first day");
+                    let last = (first + Months::new(1)).pred_opt().expect("dates before the first month are not used");
+                    first.with_day((*day).min(last.day())).expect("clamped to the month's length")
+                };
+                match in_month(due) {
+                    date if date > due  => date,
+                    _                   => in_month(due + Months::new(1)),
+                }
+            }
+            Recurrence::After{ days }       => completed + Days::new(u64::from(*days)),
+        }
+    }
+}
+
+impl fmt::Display for Recurrence{
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        match self {
+            Recurrence::Daily               => write!(f, "daily"),
+            Recurrence::Weekly{ weekdays }  => {
+                let weekdays: Vec<String> = weekdays.iter().map(|w| w.to_string().to_lowercase()).collect();
+                match weekdays.is_empty() {
+                    true    => write!(f, "weekly"),
+                    false   => write!(f, "weekly:{}", weekdays.join(",")),
+                }
+            }
+            Recurrence::Monthly{ day }      => write!(f, "monthly:{}", day),
+            Recurrence::After{ days }       => write!(f, "after:{}", days),
+        }
+    }
+}
+
+impl FromStr for Recurrence{
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Recurrence>{
+        let invalid = || Error::Invalid(format!("Invalid recurrence: {}, expected {}", input, RULES));
+        let lowercase = input.trim().to_lowercase();
+        let (rule, argument) = lowercase.split_once(':').unwrap_or((&lowercase, ""));
+
+        match (rule.trim(), argument.trim()) {
+            ("daily", "")       => Ok(Recurrence::Daily),
+            ("weekly", days)    => {
+                let weekdays = days.split(',')
+                    .map(str::trim)
+                    .filter(|day| !day.is_empty())
+                    .map(|day| day.parse::<Weekday>().map_err(|_| invalid()))
+                    .collect::<Result<_>>()?;
+                Ok(Recurrence::Weekly{ weekdays })
+            }
+            ("monthly", day)    => match day.parse() {
+                Ok(day @ 1..=31)    => Ok(Recurrence::Monthly{ day }),
+                _                   => Err(invalid()),
+            },
+            ("after", days)     => match days.trim_end_matches('d').parse() {
+                Ok(days) if days > 0    => Ok(Recurrence::After{ days }),
+                _                       => Err(invalid()),
+            },
+            _                   => Err(invalid()),
+        }
+    }
+}
+
+/// Parses a recurrence rule, or `none` (or nothing) for a task that does not repeat.
+pub fn parse(input: &str) -> Result<Option<Recurrence>>{
+    match input.trim() {
+        "" | "none" => Ok(None),
+        rule        => Ok(Some(rule.parse()?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> NaiveDate{
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn daily_is_the_next_day(){
+        assert_eq!(Recurrence::Daily.next(date("2026-12-31"), date("2027-01-05")), date("2027-01-01"));
+    }
+
+    #[test]
+    fn weekly_takes_the_next_listed_weekday(){
+        let rule: Recurrence = "weekly:mon,fri".parse().unwrap();
+        // 2026-10-18 is a Sunday.
+        assert_eq!(rule.next(date("2026-10-18"), date("2026-10-18")), date("2026-10-19"));
+        assert_eq!(rule.next(date("2026-10-19"), date("2026-10-19")), date("2026-10-23"));
+        assert_eq!(rule.next(date("2026-10-23"), date("2026-10-23")), date("2026-10-26"));
+    }
+
+    #[test]
+    fn weekly_without_weekdays_keeps_the_due_weekday(){
+        assert_eq!(Recurrence::Weekly{ weekdays: Vec::new() }.next(date("2026-10-18"), date("2026-10-20")), date("2026-10-25"));
+    }
+
+    #[test]
+    fn monthly_clamps_to_short_months(){
+        let rule = Recurrence::Monthly{ day: 31 };
+        assert_eq!(rule.next(date("2026-01-31"), date("2026-01-31")), date("2026-02-28"));
+        assert_eq!(rule.next(date("2026-02-28"), date("2026-02-28")), date("2026-03-31"));
+        assert_eq!(rule.next(date("2028-01-31"), date("2028-01-31")), date("2028-02-29"));
+    }
+
+    #[test]
+    fn monthly_stays_in_the_month_when_the_day_is_still_ahead(){
+        assert_eq!(Recurrence::Monthly{ day: 15 }.next(date("2026-10-10"), date("2026-10-10")), date("2026-10-15"));
+        assert_eq!(Recurrence::Monthly{ day: 15 }.next(date("2026-12-15"), date("2026-12-15")), date("2027-01-15"));
+    }
+
+    #[test]
+    fn after_counts_from_completion(){
+        assert_eq!(Recurrence::After{ days: 10 }.next(date("2026-10-01"), date("2026-10-18")), date("2026-10-28"));
+    }
+
+    #[test]
+    fn rules_round_trip(){
+        for rule in ["daily", "weekly", "weekly:mon,fri", "monthly:15", "after:10"] {
+            assert_eq!(rule.parse::<Recurrence>().unwrap().to_string(), rule);
+        }
+        assert_eq!(parse("after:3d").unwrap(), Some(Recurrence::After{ days: 3 }));
+        assert_eq!(parse(" none ").unwrap(), None);
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected(){
+        for rule in ["hourly", "monthly:0", "monthly:32", "after:0", "weekly:funday", "daily:2"] {
+            assert!(rule.parse::<Recurrence>().is_err(), "{} was accepted", rule);
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use serde::{
+    Serialize,
+    Deserialize
+};
+use serde_json::Value;
+
+use crate::{
+    error::{
+        Error,
+        Result
+    },
+    project,
+    task::{
+        Priority,
+        Task
+    }
+};
+
+/// On-disk schema version written by this build. Bump it whenever the stored
+/// shape of a task changes and append the matching step to `MIGRATIONS`.
+pub const VERSION: u32 = 13;
+
+/// `MIGRATIONS[n]` upgrades the tasks of a version `n` file to version `n + 1`.
+const MIGRATIONS: [fn(&mut [Value]); VERSION as usize] = [
+    v0_to_v1,
+    v1_to_v2,
+    v2_to_v3,
+    v3_to_v4,
+    v4_to_v5,
+    v5_to_v6,
+    v6_to_v7,
+    v7_to_v8,
+    v8_to_v9,
+    v9_to_v10,
+    v10_to_v11,
+    v11_to_v12,
+    v12_to_v13,
+];
+
+/// Version 0 was a bare array of tasks; version 1 wraps it in an envelope.
+/// Version 0 files could also hold what the original edit option wrote without
+/// checking it: assignees as one comma separated string and priorities in any
+/// case. Both are repaired; anything else that does not read is reported by
+/// `from_values`.
+fn v0_to_v1(tasks: &mut [Value]){
+    for task in tasks {
+        let Value::Object(task) = task else {
+            continue;
+        };
+        if let Some(Value::String(assignees)) = task.get("assignees") {
+            let assignees = assignees.split(',')
+                .map(str::trim)
+                .filter(|assignee| !assignee.is_empty())
+                .map(|assignee| Value::String(assignee.to_string()))
+                .collect();
+            task.insert("assignees".to_string(), Value::Array(assignees));
+        }
+        if let Some(priority) = task.get("priority").and_then(Value::as_str).and_then(|priority| priority.trim().parse::<Priority>().ok()) {
+            task.insert("priority".to_string(), serde_json::json!(priority));
+        }
+    }
+}
+
+/// Version 2 added the optional `parent` of subtasks.
+fn v1_to_v2(tasks: &mut [Value]){
+    for task in tasks {
+        if let Value::Object(task) = task {
+            task.entry("parent").or_insert(Value::Null);
+        }
+    }
+}
+
+/// Version 3 added `blocked_by`, the tasks that have to be completed first.
+fn v2_to_v3(tasks: &mut [Value]){
+    for task in tasks {
+        if let Value::Object(task) = task {
+            task.entry("blocked_by").or_insert(Value::Array(Vec::new()));
+        }
+    }
+}
+
+/// Version 4 added `tags`.
+fn v3_to_v4(tasks: &mut [Value]){
+    for task in tasks {
+        if let Value::Object(task) = task {
+            task.entry("tags").or_insert(Value::Array(Vec::new()));
+        }
+    }
+}
+
+/// Version 5 added the `recurrence` rule of recurring tasks and the `series` they belong to.
+fn v4_to_v5(tasks: &mut [Value]){
+    for task in tasks {
+        if let Value::Object(task) = task {
+            task.entry("recurrence").or_insert(Value::Null);
+            task.entry("series").or_insert(Value::Null);
+        }
+    }
+}
+
+/// Version 6 added more statuses, which older builds cannot read, and the
+/// `status_history` of each task.
+fn v5_to_v6(tasks: &mut [Value]){
+    for task in tasks {
+        if let Value::Object(task) = task {
+            task.entry("status_history").or_insert(Value::Array(Vec::new()));
+        }
+    }
+}
+
+/// Version 7 added the optional `due_time` with its time zone.
+fn v6_to_v7(tasks: &mut [Value]){
+    for task in tasks {
+        if let Value::Object(task) = task {
+            task.entry("due_time").or_insert(Value::Null);
+        }
+    }
+}
+
+/// Version 8 added the `worklog` of time spent on each task.
+fn v7_to_v8(tasks: &mut [Value]){
+    for task in tasks {
+        if let Value::Object(task) = task {
+            task.entry("worklog").or_insert(Value::Array(Vec::new()));
+        }
+    }
+}
+
+/// Version 9 added the optional `estimate`.
+fn v8_to_v9(tasks: &mut [Value]){
+    for task in tasks {
+        if let Value::Object(task) = task {
+            task.entry("estimate").or_insert(Value::Null);
+        }
+    }
+}
+
+/// Version 10 added the `comments` thread of each task.
+fn v9_to_v10(tasks: &mut [Value]){
+    for task in tasks {
+        if let Value::Object(task) = task {
+            task.entry("comments").or_insert(Value::Array(Vec::new()));
+        }
+    }
+}
+
+/// Version 11 added `deleted_at`, set on tasks in the trash.
+fn v10_to_v11(tasks: &mut [Value]){
+    for task in tasks {
+        if let Value::Object(task) = task {
+            task.entry("deleted_at").or_insert(Value::Null);
+        }
+    }
+}
+
+/// Version 12 added `archived_at`, set on archived tasks.
+fn v11_to_v12(tasks: &mut [Value]){
+    for task in tasks {
+        if let Value::Object(task) = task {
+            task.entry("archived_at").or_insert(Value::Null);
+        }
+    }
+}
+
+/// Version 13 added the `project` each task belongs to; older tasks join the
+/// default project.
+fn v12_to_v13(tasks: &mut [Value]){
+    for task in tasks {
+        if let Value::Object(task) = task {
+            task.entry("project").or_insert(Value::String(project::DEFAULT.to_string()));
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Envelope<'a>{
+    version:    u32,
+    tasks:      &'a [Task],
+}
+
+#[derive(Deserialize)]
+struct RawEnvelope{
+    version:    u32,
+    tasks:      Vec<Value>,
+}
+
+/// Upgrades raw tasks stored at `version` to the current schema. Tasks written
+/// by a newer build are left untouched.
+pub fn migrate(version: u32, tasks: &mut [Value]){
+    for step in MIGRATIONS.iter().skip(version as usize) {
+        step(tasks);
+    }
+}
+
+/// Refuses to overwrite data written by a newer build, which could hold fields
+/// this build would silently drop.
+pub fn check_writable(version: u32) -> Result<()>{
+    if version > VERSION {
+        return Err(Error::NewerSchema(version));
+    }
+
+    Ok(())
+}
+
+/// Reads upgraded tasks, naming the first one that cannot be read so it can be
+/// fixed by hand.
+pub fn from_values(tasks: Vec<Value>) -> Result<Vec<Task>>{
+    tasks.into_iter()
+        .map(|task| {
+            let name = |field: &str| task.get(field).map(Value::to_string).unwrap_or_else(|| "(none)".to_string());
+            let described = format!("ID: {}, title: {}", name("id"), name("title"));
+            serde_json::from_value(task).map_err(|err| Error::Invalid(format!("Task with {} cannot be read: {}", described, err)))
+        })
+        .collect()
+}
+
+/// Parses a task file of any known version, returning the version it was stored
+/// at together with the tasks upgraded to the current schema.
+pub fn decode(contents: &str) -> Result<(u32, Vec<Task>)>{
+    if contents.trim().is_empty() {
+        return Ok((VERSION, Vec::new()));
+    }
+
+    let (version, mut tasks) = match serde_json::from_str(contents)? {
+        Value::Array(tasks) => (0, tasks),
+        envelope            => {
+            let RawEnvelope { version, tasks } = serde_json::from_value(envelope)?;
+            (version, tasks)
+        }
+    };
+
+    migrate(version, &mut tasks);
+
+    Ok((version, from_values(tasks)?))
+}
+
+pub fn encode(tasks: &[Task]) -> Result<String>{
+    Ok(serde_json::to_string_pretty(&Envelope { version: VERSION, tasks })?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Status;
+
+    /// A task file as the first release wrote it, after option 5 edited the
+    /// assignees and priority of the first task.
+    const V0: &str = r#"[
+        {"id": 1, "uuid": "8c4f4a2e-54a8-4f7e-9f0b-0f3d8f1b2a01", "priority": "high", "title": "Deploy staging",
+         "description": "", "due_date": "2026-10-20", "status": "NotCompleted", "assignees": "alice, bob,"},
+        {"id": 2, "uuid": "8c4f4a2e-54a8-4f7e-9f0b-0f3d8f1b2a02", "priority": "Low", "title": "Write notes",
+         "description": "Release notes", "due_date": "2026-10-21", "status": "Completed", "assignees": ["carol"]}
+    ]"#;
+
+    /// A version 12 file, written before tasks belonged to projects.
+    const V12: &str = r#"{"version": 12, "tasks": [
+        {"id": 4, "uuid": "8c4f4a2e-54a8-4f7e-9f0b-0f3d8f1b2a04", "priority": "Medium", "title": "Archived",
+         "description": "", "due_date": "2026-10-22", "due_time": null, "status": "Cancelled", "assignees": [],
+         "parent": null, "blocked_by": [], "tags": ["ops"], "recurrence": {"rule": "monthly", "day": 15}, "series": null,
+         "status_history": [], "worklog": [], "estimate": null, "comments": [], "deleted_at": null,
+         "archived_at": "2026-10-01T09:00:00Z"}
+    ]}"#;
+
+    #[test]
+    fn version_0_files_are_repaired(){
+        let (version, tasks) = decode(V0).unwrap();
+        assert_eq!(version, 0);
+        assert_eq!(tasks.len(), 2);
+
+        assert_eq!(tasks[0].assignees, ["alice", "bob"]);
+        assert_eq!(tasks[0].priority, Priority::High);
+        assert_eq!(tasks[0].project, project::DEFAULT);
+        assert!(tasks[0].tags.is_empty() && tasks[0].parent.is_none() && tasks[0].archived_at.is_none());
+
+        assert_eq!(tasks[1].assignees, ["carol"]);
+        assert_eq!(tasks[1].status, Status::Completed);
+    }
+
+    #[test]
+    fn version_12_files_join_the_default_project(){
+        let (version, tasks) = decode(V12).unwrap();
+        assert_eq!(version, 12);
+        assert_eq!(tasks[0].project, project::DEFAULT);
+        assert!(tasks[0].archived_at.is_some());
+        assert_eq!(tasks[0].recurrence.as_ref().map(ToString::to_string).as_deref(), Some("monthly:15"));
+    }
+
+    #[test]
+    fn encoded_files_decode_unchanged(){
+        let (_, tasks) = decode(V0).unwrap();
+        let (version, again) = decode(&encode(&tasks).unwrap()).unwrap();
+        assert_eq!(version, VERSION);
+        assert_eq!(serde_json::to_value(&tasks).unwrap(), serde_json::to_value(&again).unwrap());
+    }
+
+    #[test]
+    fn empty_files_hold_no_tasks(){
+        assert!(decode("").unwrap().1.is_empty());
+        assert!(decode("  \n").unwrap().1.is_empty());
+    }
+
+    #[test]
+    fn tasks_that_cannot_be_repaired_are_named(){
+        let file = V0.replace(r#""priority": "high""#, r#""priority": "urgent""#);
+        let Err(Error::Invalid(message)) = decode(&file) else {
+            panic!("expected the task to be reported");
+        };
+        assert!(message.starts_with(r#"Task with ID: 1, title: "Deploy staging" cannot be read"#), "{}", message);
+    }
+
+    #[test]
+    fn newer_files_are_not_written(){
+        assert!(check_writable(VERSION).is_ok());
+        assert!(matches!(check_writable(VERSION + 1), Err(Error::NewerSchema(_))));
+    }
+}
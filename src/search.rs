@@ -0,0 +1,262 @@
+use fuzzy_matcher::{
+    FuzzyMatcher,
+    skim::SkimMatcherV2
+};
+use owo_colors::{
+    OwoColorize,
+    colors::css::Aqua
+};
+use regex::{
+    Regex,
+    RegexBuilder
+};
+use std::ops::Range;
+
+use crate::{
+    config::Config,
+    error::{
+        Error,
+        Result
+    },
+    task::Task,
+    view
+};
+
+/// How the query text is matched against a task's title, description and assignees.
+#[derive(Debug,Clone,Copy,Default,PartialEq)]
+pub enum Mode{
+    /// Every whitespace separated word must occur somewhere, ignoring case.
+    #[default]
+    Substring,
+    /// A regular expression, case-insensitive unless it says otherwise with `(?-i)`.
+    Regex,
+    /// The query's characters must appear in order, not necessarily adjacent.
+    Fuzzy,
+}
+
+/// A task matched by a full-text search, with the byte ranges to highlight.
+pub struct Hit{
+    pub task:           Task,
+    pub score:          i64,
+    pub title:          Vec<Range<usize>>,
+    pub description:    Vec<Range<usize>>,
+    /// One entry per assignee, in the same order as `task.assignees`.
+    pub assignees:      Vec<Vec<Range<usize>>>,
+}
+
+/// Matches in the title count more than matches in assignees, which count more
+/// than matches in the description.
+const TITLE_WEIGHT: i64 = 3;
+const ASSIGNEE_WEIGHT: i64 = 2;
+const DESCRIPTION_WEIGHT: i64 = 1;
+
+enum Matcher{
+    Patterns(Vec<Regex>),
+    Fuzzy(Box<SkimMatcherV2>, String),
+}
+
+fn build_regex(pattern: &str) -> Result<Regex>{
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|err| Error::Invalid(format!("Invalid regular expression: {}", err)))
+}
+
+impl Matcher{
+    fn new(query: &str, mode: Mode) -> Result<Matcher>{
+        match mode {
+            Mode::Substring => Ok(Matcher::Patterns(
+                query.split_whitespace()
+                    .map(|word| build_regex(&regex::escape(word)))
+                    .collect::<Result<_>>()?
+            )),
+            Mode::Regex     => Ok(Matcher::Patterns(vec![build_regex(query)?])),
+            Mode::Fuzzy     => Ok(Matcher::Fuzzy(Box::new(SkimMatcherV2::default().ignore_case()), query.to_string())),
+        }
+    }
+
+    /// Scores one piece of text against each pattern. `per_pattern[i]` is set
+    /// when pattern `i` matched, so callers can require every pattern to match.
+    fn score(&self, text: &str, weight: i64, per_pattern: &mut [bool], ranges: &mut Vec<Range<usize>>) -> i64{
+        match self {
+            Matcher::Patterns(patterns) => {
+                let mut score = 0;
+                for (pattern, matched) in patterns.iter().zip(per_pattern.iter_mut()) {
+                    for found in pattern.find_iter(text).filter(|m| !m.is_empty()) {
+                        *matched = true;
+                        // Matches at the start of a word rank above matches inside one.
+                        let word_start = text[..found.start()].chars().next_back().is_none_or(|c| !c.is_alphanumeric());
+                        score += weight * if word_start { 2 } else { 1 };
+                        ranges.push(found.range());
+                    }
+                    if pattern.find(text).is_some_and(|m| m.range() == (0..text.len())) {
+                        score += weight * 2;
+                    }
+                }
+                score
+            }
+            Matcher::Fuzzy(matcher, query) => {
+                match matcher.fuzzy_indices(text, query) {
+                    Some((score, indices)) => {
+                        per_pattern[0] = true;
+                        let offsets: Vec<(usize, char)> = text.char_indices().collect();
+                        ranges.extend(indices.into_iter().filter_map(|index| {
+                            offsets.get(index).map(|(start, c)| *start..*start + c.len_utf8())
+                        }));
+                        score * weight
+                    }
+                    None => 0,
+                }
+            }
+        }
+    }
+
+    fn pattern_count(&self) -> usize{
+        match self {
+            Matcher::Patterns(patterns) => patterns.len(),
+            Matcher::Fuzzy(..)          => 1,
+        }
+    }
+}
+
+/// Searches the title, description and assignees of `tasks`, returning the
+/// matching tasks best match first.
+pub fn search(tasks: Vec<Task>, query: &str, mode: Mode) -> Result<Vec<Hit>>{
+    if query.trim().is_empty() {
+        return Err(Error::Invalid("The search text cannot be empty".to_string()));
+    }
+
+    let matcher = Matcher::new(query, mode)?;
+    let mut hits = Vec::new();
+
+    for task in tasks {
+        let mut matched = vec![false; matcher.pattern_count()];
+        let mut title = Vec::new();
+        let mut description = Vec::new();
+
+        let mut score = matcher.score(&task.title, TITLE_WEIGHT, &mut matched, &mut title);
+        score += matcher.score(&task.description, DESCRIPTION_WEIGHT, &mut matched, &mut description);
+
+        let mut assignees = Vec::with_capacity(task.assignees.len());
+        for assignee in &task.assignees {
+            let mut ranges = Vec::new();
+            score += matcher.score(assignee, ASSIGNEE_WEIGHT, &mut matched, &mut ranges);
+            assignees.push(ranges);
+        }
+
+        if matched.iter().all(|m| *m) {
+            hits.push(Hit{ task, score, title, description, assignees });
+        }
+    }
+
+    hits.sort_by(|a, b| b.score.cmp(&a.score).then(a.task.id.cmp(&b.task.id)));
+
+    Ok(hits)
+}
+
+/// Renders `text` with the given byte ranges highlighted.
+fn highlight(text: &str, ranges: &[Range<usize>]) -> String{
+    let mut ranges = ranges.to_vec();
+    ranges.sort_by_key(|range| range.start);
+
+    let mut output = String::new();
+    let mut position = 0;
+    for range in ranges {
+        if range.end <= position {
+            continue;
+        }
+        let start = range.start.max(position);
+        output.push_str(&text[position..start]);
+        output.push_str(&(&text[start..range.end]).black().on_yellow().to_string());
+        position = range.end;
+    }
+    output.push_str(&text[position..]);
+
+    output
+}
+
+pub fn print_hits(config: &Config, query: &str, hits: &[Hit]){
+    if hits.is_empty() {
+        println!("No tasks found matching {}", query);
+        return;
+    }
+
+    println!("{} tasks found matching {}", hits.len(), query);
+    println!("{}","-".repeat(110));
+    for hit in hits {
+        println!("{} {}  ({}, {}, due {})",
+            format!("[{}]", config.key(&hit.task)).bold().fg::<Aqua>(),
+            highlight(&hit.task.title, &hit.title).bold(),
+            hit.task.priority,
+            hit.task.status,
+            hit.task.due(),
+        );
+
+        if !hit.description.is_empty() {
+            println!("    {}: {}", r#"Description"#.underline().fg::<Aqua>(), highlight(&hit.task.description, &hit.description));
+        }
+
+        let assignees: Vec<String> = hit.task.assignees.iter()
+            .zip(&hit.assignees)
+            .map(|(assignee, ranges)| highlight(assignee, ranges))
+            .collect();
+        println!("    {}: {}", r#"Assignees"#.underline().fg::<Aqua>(), assignees.join(", "));
+        if !hit.task.tags.is_empty() {
+            println!("    {}: {}", r#"Tags"#.underline().fg::<Aqua>(), view::colored_tags(config, &hit.task.tags));
+        }
+    }
+    println!("{}","-".repeat(110));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+
+    fn task(id: i32, title: &str, description: &str, assignees: &[&str]) -> Task{
+        let mut task = Task::new(Priority::Medium, title.to_string(), description.to_string(), "2026-10-20".parse().unwrap(), assignees.iter().map(|a| a.to_string()).collect());
+        task.id = id;
+        task
+    }
+
+    fn tasks() -> Vec<Task>{
+        vec![
+            task(1, "Write release notes", "Mention the deploy", &["alice"]),
+            task(2, "Deploy staging", "", &["bob"]),
+            task(3, "Review budget", "Ask DEPLOY team", &["deploy-bot"]),
+            task(4, "Redeploy cache", "", &["carol"]),
+        ]
+    }
+
+    fn ids(query: &str, mode: Mode) -> Vec<i32>{
+        search(tasks(), query, mode).unwrap().iter().map(|hit| hit.task.id).collect()
+    }
+
+    #[test]
+    fn substrings_ignore_case_and_rank_titles_first(){
+        assert_eq!(ids("DePloY", Mode::Substring), [2, 3, 4, 1]);
+        assert_eq!(ids("release MENTION", Mode::Substring), [1]);
+        assert!(ids("release budget", Mode::Substring).is_empty());
+    }
+
+    #[test]
+    fn regexes_ignore_case_unless_told_otherwise(){
+        assert_eq!(ids("^re", Mode::Regex), [3, 4]);
+        assert_eq!(ids("(?-i)DEPLOY", Mode::Regex), [3]);
+        assert!(search(tasks(), "(", Mode::Regex).is_err());
+    }
+
+    #[test]
+    fn fuzzy_matches_characters_in_order(){
+        let hits = ids("dplstg", Mode::Fuzzy);
+        assert_eq!(hits.first(), Some(&2));
+        assert!(!hits.contains(&4));
+    }
+
+    #[test]
+    fn highlights_cover_the_match(){
+        let hits = search(tasks(), "notes", Mode::Substring).unwrap();
+        assert_eq!(hits[0].title.as_slice(), &[Range{ start: 14, end: 19 }]);
+        assert!(search(tasks(), "  ", Mode::Substring).is_err());
+    }
+}
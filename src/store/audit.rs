@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::{
+    error::Result,
+    history::{
+        Action,
+        Event
+    },
+    task::Task
+};
+
+use super::TaskStore;
+
+/// Wraps a store so every task it creates, changes or deletes is recorded in
+/// the activity log under `actor`, grouped into operations that can be undone.
+///
+/// The state before a change is taken from the tasks last read through this
+/// store, so recording never rereads the tasks between a read and a write.
+pub struct Audited{
+    inner:  Box<dyn TaskStore>,
+    actor:  String,
+    op:     Uuid,
+    action: Action,
+    seen:   HashMap<Uuid, Task>,
+}
+
+impl Audited{
+    pub fn new(inner: Box<dyn TaskStore>, actor: String) -> Audited{
+        Audited{ inner, actor, op: Uuid::new_v4(), action: Action::Change, seen: HashMap::new() }
+    }
+
+    fn event(&self, before: Option<Task>, after: Option<Task>) -> Event{
+        Event::new(&self.actor, self.op, self.action, before, after)
+    }
+
+    /// The last read state of the tasks with the given UUIDs.
+    fn before(&mut self, uuids: &[Uuid]) -> Result<Vec<Option<Task>>>{
+        if uuids.iter().any(|uuid| !self.seen.contains_key(uuid)) {
+            self.load()?;
+        }
+
+        Ok(uuids.iter().map(|uuid| self.seen.get(uuid).cloned()).collect())
+    }
+}
+
+fn changed(before: &Task, after: &Task) -> bool{
+    serde_json::to_value(before).ok() != serde_json::to_value(after).ok()
+}
+
+impl TaskStore for Audited{
+    fn load(&mut self) -> Result<Vec<Task>>{
+        let tasks = self.inner.load()?;
+        self.seen = tasks.iter().map(|task| (task.uuid, task.clone())).collect();
+
+        Ok(tasks)
+    }
+
+    fn insert(&mut self, task: &Task) -> Result<()>{
+        self.inner.insert(task)?;
+        self.seen.insert(task.uuid, task.clone());
+
+        self.inner.record(&[self.event(None, Some(task.clone()))])
+    }
+
+    fn update_many(&mut self, tasks: &[Task]) -> Result<()>{
+        let uuids: Vec<Uuid> = tasks.iter().map(|task| task.uuid).collect();
+        let before = self.before(&uuids)?;
+        self.inner.update_many(tasks)?;
+
+        let events: Vec<Event> = before.into_iter()
+            .zip(tasks)
+            .filter(|(before, after)| before.as_ref().is_none_or(|before| changed(before, after)))
+            .map(|(before, after)| self.event(before, Some(after.clone())))
+            .collect();
+        for task in tasks {
+            self.seen.insert(task.uuid, task.clone());
+        }
+
+        match events.is_empty() {
+            true    => Ok(()),
+            false   => self.inner.record(&events),
+        }
+    }
+
+    fn delete(&mut self, uuid: &Uuid) -> Result<()>{
+        let before = self.before(std::slice::from_ref(uuid))?.remove(0);
+        self.inner.delete(uuid)?;
+        self.seen.remove(uuid);
+
+        self.inner.record(&[self.event(before, None)])
+    }
+
+    fn begin_operation(&mut self, action: Action){
+        self.op = Uuid::new_v4();
+        self.action = action;
+        self.inner.begin_operation(action);
+    }
+
+    fn record(&mut self, events: &[Event]) -> Result<()>{
+        self.inner.record(events)
+    }
+
+    fn events(&mut self) -> Result<Vec<Event>>{
+        self.inner.events()
+    }
+}
@@ -0,0 +1,196 @@
+use std::{
+    fs::{
+        self,
+        OpenOptions
+    },
+    io::{
+        ErrorKind,
+        Write
+    },
+    path::PathBuf
+};
+use uuid::Uuid;
+
+use crate::{
+    atomic::{
+        self,
+        FileLock
+    },
+    error::{
+        Error,
+        Result
+    },
+    history::{
+        self,
+        Action,
+        Event
+    },
+    schema,
+    task::Task
+};
+
+use super::{
+    Snapshots,
+    TaskStore,
+    duplicate_id
+};
+
+/// Keeps every task in a single pretty-printed JSON file, wrapped in a
+/// versioned envelope (see `schema`).
+///
+/// Writes go through a temporary file and a rename while holding an exclusive
+/// lock, and updates or deletions are refused with `Error::Conflict` when a
+/// task they touch changed on disk since this store first read it in the
+/// current operation. The activity log is kept beside it in `activity.jsonl`,
+/// one event per line.
+pub struct JsonStore{
+    path:           PathBuf,
+    snapshots:      Snapshots,
+    unique_ids:     bool,
+}
+
+impl JsonStore{
+    pub fn new(path: PathBuf) -> JsonStore{
+        JsonStore{ path, snapshots: Snapshots::default(), unique_ids: true }
+    }
+
+    /// Lets several tasks share an ID, as in the trash or the archive where a
+    /// task keeps the ID a newer task may have taken since.
+    pub fn with_duplicate_ids(mut self) -> JsonStore{
+        self.unique_ids = false;
+        self
+    }
+
+    fn activity_path(&self) -> PathBuf{
+        self.path.with_file_name("activity.jsonl")
+    }
+
+    fn read_contents(&self) -> Result<String>{
+        match fs::read_to_string(&self.path) {
+            Ok(contents)                                    => Ok(contents),
+            Err(err) if err.kind() == ErrorKind::NotFound   => Ok(String::new()),
+            Err(err)                                        => Err(err.into()),
+        }
+    }
+
+    /// Applies `change` to the tasks currently on disk and saves the result.
+    /// The change is refused if another process modified any of the `touched`
+    /// tasks since they were read here.
+    fn modify(&mut self, touched: &[Uuid], change: impl FnOnce(&mut Vec<Task>) -> Result<()>) -> Result<()>{
+        let _lock = FileLock::exclusive(&self.path)?;
+
+        let (version, mut tasks) = schema::decode(&self.read_contents()?)?;
+        schema::check_writable(version)?;
+        for task in tasks.iter().filter(|t| touched.contains(&t.uuid)) {
+            if !self.snapshots.unchanged(&task.uuid, &serde_json::to_string(task)?) {
+                return Err(Error::Conflict(self.path.clone()));
+            }
+        }
+        change(&mut tasks)?;
+
+        let updated_json = schema::encode(&tasks)?;
+        atomic::write(&self.path, updated_json.as_bytes())?;
+        for task in tasks.iter().filter(|t| touched.contains(&t.uuid)) {
+            self.snapshots.wrote(task.uuid, &serde_json::to_string(task)?);
+        }
+
+        Ok(())
+    }
+}
+
+impl TaskStore for JsonStore{
+    fn load(&mut self) -> Result<Vec<Task>>{
+        let contents = {
+            let _lock = FileLock::shared(&self.path)?;
+            self.read_contents()?
+        };
+
+        let (_, tasks) = schema::decode(&contents)?;
+        for task in &tasks {
+            self.snapshots.read(task.uuid, &serde_json::to_string(task)?);
+        }
+
+        Ok(tasks)
+    }
+
+    fn insert(&mut self, task: &Task) -> Result<()>{
+        let unique_ids = self.unique_ids;
+        self.modify(&[task.uuid], |tasks| {
+            if unique_ids && tasks.iter().any(|t| t.id == task.id) {
+                return Err(Error::DuplicateId(task.id));
+            }
+
+            tasks.push(task.clone());
+            Ok(())
+        })
+    }
+
+    fn update_many(&mut self, updated: &[Task]) -> Result<()>{
+        let unique_ids = self.unique_ids;
+        let uuids: Vec<Uuid> = updated.iter().map(|t| t.uuid).collect();
+        self.modify(&uuids, |tasks| {
+            let mut assigned = Vec::new();
+
+            for task in updated {
+                let stored = tasks.iter_mut()
+                    .find(|t| t.uuid == task.uuid)
+                    .ok_or_else(|| Error::Invalid(format!("Task with UUID: {} not found", task.uuid)))?;
+
+                if unique_ids && stored.id != task.id {
+                    assigned.push(task.id);
+                }
+                *stored = task.clone();
+            }
+
+            match duplicate_id(tasks, &assigned) {
+                Some(id)    => Err(Error::DuplicateId(id)),
+                None        => Ok(()),
+            }
+        })
+    }
+
+    fn delete(&mut self, uuid: &Uuid) -> Result<()>{
+        self.modify(&[*uuid], |tasks| {
+            let index = tasks.iter()
+                .position(|t| t.uuid == *uuid)
+                .ok_or_else(|| Error::Invalid(format!("Task with UUID: {} not found", uuid)))?;
+            tasks.remove(index);
+            Ok(())
+        })
+    }
+
+    fn begin_operation(&mut self, _action: Action){
+        self.snapshots.clear();
+    }
+
+    fn record(&mut self, events: &[Event]) -> Result<()>{
+        let path = self.activity_path();
+        let _lock = FileLock::exclusive(&path)?;
+
+        let mut lines = String::new();
+        for event in events {
+            lines.push_str(&history::encode(event)?);
+            lines.push('\n');
+        }
+        OpenOptions::new().create(true).append(true).open(&path)?.write_all(lines.as_bytes())?;
+
+        Ok(())
+    }
+
+    fn events(&mut self) -> Result<Vec<Event>>{
+        let path = self.activity_path();
+        let contents = {
+            let _lock = FileLock::shared(&path)?;
+            match fs::read_to_string(&path) {
+                Ok(contents)                                    => contents,
+                Err(err) if err.kind() == ErrorKind::NotFound   => String::new(),
+                Err(err)                                        => return Err(err.into()),
+            }
+        };
+
+        contents.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(history::decode)
+            .collect()
+    }
+}
@@ -0,0 +1,246 @@
+mod audit;
+mod json;
+mod sqlite;
+
+use std::{
+    collections::HashMap,
+    hash::{
+        DefaultHasher,
+        Hash,
+        Hasher
+    },
+    path::Path
+};
+use uuid::Uuid;
+
+use crate::{
+    config::{
+        Backend,
+        Config
+    },
+    error::Result,
+    history::{
+        Action,
+        Event
+    },
+    task::Task
+};
+
+pub use audit::Audited;
+pub use json::JsonStore;
+pub use sqlite::SqliteStore;
+
+/// Persistence for tasks. Mutations only touch the tasks they are given, so
+/// backends that support it only rewrite the affected records.
+///
+/// Updates and deletions are refused with `Error::Conflict` when a task they
+/// touch was changed by someone else since this store first read it in the
+/// current operation, however often it was read again in between.
+///
+/// Task IDs are unique: `insert` and `update_many` fail with
+/// `Error::DuplicateId` rather than give a task an ID another task already
+/// uses. Duplicates left by older releases are tolerated until renumbered.
+pub trait TaskStore{
+    /// Returns every stored task in insertion order.
+    fn load(&mut self) -> Result<Vec<Task>>;
+
+    fn insert(&mut self, task: &Task) -> Result<()>;
+
+    /// Replaces the stored tasks that have the same `uuid`s in one atomic step,
+    /// so IDs can be swapped or renumbered without passing through a duplicate.
+    fn update_many(&mut self, tasks: &[Task]) -> Result<()>;
+
+    fn update(&mut self, task: &Task) -> Result<()>{
+        self.update_many(std::slice::from_ref(task))
+    }
+
+    fn delete(&mut self, uuid: &Uuid) -> Result<()>;
+
+    /// Returns the tasks matching `filter`, in insertion order.
+    fn query(&mut self, filter: &dyn Fn(&Task) -> bool) -> Result<Vec<Task>>{
+        Ok(self.load()?.into_iter().filter(|task| filter(task)).collect())
+    }
+
+    /// Starts a new operation: the changes from here on are undone together,
+    /// and checked for conflicts against the tasks as read from here on.
+    fn begin_operation(&mut self, action: Action);
+
+    /// Appends to the activity log.
+    fn record(&mut self, events: &[Event]) -> Result<()>;
+
+    /// Returns the activity log, oldest first.
+    fn events(&mut self) -> Result<Vec<Event>>;
+}
+
+/// Each task as a store first read it in the current operation, kept as a
+/// fingerprint of its stored JSON.
+#[derive(Default)]
+struct Snapshots(HashMap<Uuid, u64>);
+
+impl Snapshots{
+    fn fingerprint(data: &str) -> u64{
+        let mut hasher = DefaultHasher::new();
+        data.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Notes a task as read, unless it was read before in this operation.
+    fn read(&mut self, uuid: Uuid, data: &str){
+        self.0.entry(uuid).or_insert_with(|| Self::fingerprint(data));
+    }
+
+    /// Notes a task as written by this store.
+    fn wrote(&mut self, uuid: Uuid, data: &str){
+        self.0.insert(uuid, Self::fingerprint(data));
+    }
+
+    /// Whether `data`, a task as stored now, is what was read or written here.
+    /// Tasks never read here are not checked.
+    fn unchanged(&self, uuid: &Uuid, data: &str) -> bool{
+        self.0.get(uuid).is_none_or(|seen| *seen == Self::fingerprint(data))
+    }
+
+    fn clear(&mut self){
+        self.0.clear();
+    }
+}
+
+/// Returns the first of the newly `assigned` IDs that more than one of `tasks` uses.
+fn duplicate_id(tasks: &[Task], assigned: &[i32]) -> Option<i32>{
+    assigned.iter()
+        .copied()
+        .find(|id| tasks.iter().filter(|t| t.id == *id).count() > 1)
+}
+
+/// Opens the backend selected in `config`, keeping its files inside `dir`.
+/// Every change made through it is recorded in the activity log under the
+/// configured user.
+pub fn open(config: &Config, dir: &Path) -> Result<Box<dyn TaskStore>>{
+    let store: Box<dyn TaskStore> = match config.backend {
+        Backend::Json   => Box::new(JsonStore::new(dir.join("tasks.json"))),
+        Backend::Sqlite => Box::new(SqliteStore::open(&dir.join("tasks.db"), "tasks")?),
+    };
+
+    Ok(Box::new(Audited::new(store, config.user())))
+}
+
+/// Opens tasks the backend selected in `config` keeps aside from the list:
+/// the `trash`, removed tasks waiting to be restored or purged, or the
+/// `archive` of closed tasks.
+pub fn open_aside(config: &Config, dir: &Path, name: &'static str) -> Result<Box<dyn TaskStore>>{
+    Ok(match config.backend {
+        Backend::Json   => Box::new(JsonStore::new(dir.join(format!("{}.json", name))).with_duplicate_ids()),
+        Backend::Sqlite => Box::new(SqliteStore::open(&dir.join("tasks.db"), name)?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error::Error,
+        paths::TempDir,
+        task::Priority
+    };
+    use std::path::PathBuf;
+
+    fn task(id: i32, title: &str) -> Task{
+        let mut task = Task::new(Priority::Medium, title.to_string(), String::new(), "2026-10-20".parse().unwrap(), Vec::new());
+        task.id = id;
+        task
+    }
+
+    /// The settings and a data directory for each backend.
+    fn backends(dir: &Path) -> Vec<(Config, PathBuf)>{
+        [Backend::Json, Backend::Sqlite].into_iter()
+            .map(|backend| {
+                let dir = dir.join(format!("{:?}", backend));
+                std::fs::create_dir_all(&dir).unwrap();
+                (Config{ backend, ..Config::default() }, dir)
+            })
+            .collect()
+    }
+
+    fn titles(store: &mut dyn TaskStore) -> Vec<String>{
+        store.load().unwrap().into_iter().map(|t| t.title).collect()
+    }
+
+    #[test]
+    fn tasks_round_trip_in_insertion_order(){
+        let dir = TempDir::new();
+        for (config, dir) in backends(&dir.path) {
+            let mut store = open(&config, &dir).unwrap();
+            let (mut first, second) = (task(2, "First"), task(1, "Second"));
+            store.insert(&first).unwrap();
+            store.insert(&second).unwrap();
+            assert_eq!(titles(store.as_mut()), ["First", "Second"]);
+
+            first.title = "Renamed".to_string();
+            store.update(&first).unwrap();
+            store.delete(&second.uuid).unwrap();
+            assert_eq!(titles(store.as_mut()), ["Renamed"]);
+            assert!(store.delete(&second.uuid).is_err());
+            assert_eq!(store.events().unwrap().len(), 4);
+        }
+    }
+
+    #[test]
+    fn ids_are_unique_except_aside(){
+        let dir = TempDir::new();
+        for (config, dir) in backends(&dir.path) {
+            let (mut store, mut trash) = (open(&config, &dir).unwrap(), open_aside(&config, &dir, "trash").unwrap());
+            let (first, mut second) = (task(1, "First"), task(2, "Second"));
+            store.insert(&first).unwrap();
+            store.insert(&second).unwrap();
+            assert!(matches!(store.insert(&task(1, "Copy")), Err(Error::DuplicateId(1))));
+
+            second.id = 1;
+            assert!(matches!(store.update(&second), Err(Error::DuplicateId(1))));
+            assert_eq!(titles(store.as_mut()), ["First", "Second"]);
+
+            // Swapping IDs in one step never passes through a duplicate.
+            let mut tasks = store.load().unwrap();
+            (tasks[0].id, tasks[1].id) = (2, 1);
+            store.update_many(&tasks).unwrap();
+            assert_eq!(store.query(&|t| t.id == 1).unwrap()[0].title, "Second");
+
+            trash.insert(&first).unwrap();
+            trash.insert(&task(2, "Trashed")).unwrap();
+            assert_eq!(titles(trash.as_mut()), ["First", "Trashed"]);
+        }
+    }
+
+    #[test]
+    fn changes_since_the_first_read_are_conflicts(){
+        let dir = TempDir::new();
+        for (config, dir) in backends(&dir.path) {
+            let (mut store, mut other) = (open(&config, &dir).unwrap(), open(&config, &dir).unwrap());
+            store.insert(&task(1, "First")).unwrap();
+            store.insert(&task(2, "Second")).unwrap();
+
+            store.begin_operation(Action::Change);
+            let mut tasks = store.load().unwrap();
+            let mut changed = other.load().unwrap().remove(0);
+            changed.title = "Changed elsewhere".to_string();
+            other.update(&changed).unwrap();
+
+            // Reading again does not make the stale copy current.
+            store.load().unwrap();
+            tasks[0].title = "Stale".to_string();
+            assert!(matches!(store.update(&tasks[0]), Err(Error::Conflict(_))));
+            assert!(matches!(store.delete(&tasks[0].uuid), Err(Error::Conflict(_))));
+            // Tasks nobody else changed can still be saved, more than once.
+            tasks[1].title = "Mine".to_string();
+            store.update(&tasks[1]).unwrap();
+            tasks[1].title = "Mine again".to_string();
+            store.update(&tasks[1]).unwrap();
+            assert_eq!(titles(other.as_mut()), ["Changed elsewhere", "Mine again"]);
+
+            // The next operation starts from what it reads.
+            store.begin_operation(Action::Change);
+            let mut tasks = store.load().unwrap();
+            tasks[0].title = "Fresh".to_string();
+            store.update(&tasks[0]).unwrap();
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use rusqlite::{
+    params,
+    Connection,
+    OptionalExtension,
+    TransactionBehavior
+};
+use std::path::{
+    Path,
+    PathBuf
+};
+use uuid::Uuid;
+
+use crate::{
+    error::{
+        Error,
+        Result
+    },
+    history::{
+        self,
+        Action,
+        Event
+    },
+    schema,
+    task::Task
+};
+
+use super::{
+    Snapshots,
+    TaskStore
+};
+
+/// Keeps one row per task in an embedded SQLite database. The task itself is
+/// stored as JSON so new `Task` fields never need a table migration; the schema
+/// version of those documents is kept in `PRAGMA user_version`. The activity
+/// log is an `activity` table with one event per row.
+///
+/// Each store reads and writes one table of tasks, `tasks`, `trash` or
+/// `archive`, and only `tasks` requires unique IDs. A row is compared with the
+/// row as first read in the current operation inside the transaction that
+/// updates or deletes it.
+pub struct SqliteStore{
+    conn:       Connection,
+    path:       PathBuf,
+    version:    u32,
+    table:      &'static str,
+    snapshots:  Snapshots,
+}
+
+/// The tables holding tasks, all migrated together since they share the
+/// database's schema version.
+const TABLES: [&str; 3] = ["tasks", "trash", "archive"];
+
+impl SqliteStore{
+    pub fn open(path: &Path, table: &'static str) -> Result<SqliteStore>{
+        let mut conn = Connection::open(path)?;
+
+        let existed: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'tasks')",
+            [],
+            |row| row.get(0),
+        )?;
+        let mut version: u32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS tasks (
+                uuid    TEXT PRIMARY KEY,
+                id      INTEGER NOT NULL,
+                data    TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS tasks_id ON tasks (id);
+            CREATE TABLE IF NOT EXISTS trash (
+                uuid    TEXT PRIMARY KEY,
+                id      INTEGER NOT NULL,
+                data    TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS archive (
+                uuid    TEXT PRIMARY KEY,
+                id      INTEGER NOT NULL,
+                data    TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS activity (
+                seq     INTEGER PRIMARY KEY AUTOINCREMENT,
+                data    TEXT NOT NULL
+            );"
+        )?;
+
+        if !existed {
+            version = schema::VERSION;
+            conn.pragma_update(None, "user_version", version)?;
+        } else if version < schema::VERSION {
+            Self::migrate(&mut conn, version)?;
+            version = schema::VERSION;
+        }
+
+        Ok(SqliteStore{ conn, path: path.to_path_buf(), version, table, snapshots: Snapshots::default() })
+    }
+
+    /// Rewrites every row from `version` to the current schema in one transaction.
+    fn migrate(conn: &mut Connection, version: u32) -> Result<()>{
+        let tx = conn.transaction()?;
+
+        for table in TABLES {
+            let rows: Vec<(String, String)> = tx.prepare(&format!("SELECT uuid, data FROM {} ORDER BY rowid", table))?
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<rusqlite::Result<_>>()?;
+
+            let mut uuids = Vec::with_capacity(rows.len());
+            let mut tasks = Vec::with_capacity(rows.len());
+            for (uuid, data) in rows {
+                uuids.push(uuid);
+                tasks.push(serde_json::from_str(&data)?);
+            }
+
+            schema::migrate(version, &mut tasks);
+
+            for (uuid, task) in uuids.iter().zip(schema::from_values(tasks)?) {
+                tx.execute(&format!("UPDATE {} SET data = ?2 WHERE uuid = ?1", table), params![uuid, serde_json::to_string(&task)?])?;
+            }
+        }
+        tx.pragma_update(None, "user_version", schema::VERSION)?;
+
+        Ok(tx.commit()?)
+    }
+}
+
+/// The stored ID and data of the task with the given UUID in `table`.
+fn stored(tx: &Connection, table: &str, uuid: &Uuid) -> Result<(i32, String)>{
+    tx.query_row(&format!("SELECT id, data FROM {} WHERE uuid = ?1", table), params![uuid.to_string()], |row| Ok((row.get(0)?, row.get(1)?)))
+        .optional()?
+        .ok_or_else(|| Error::Invalid(format!("Task with UUID: {} not found", uuid)))
+}
+
+impl TaskStore for SqliteStore{
+    fn load(&mut self) -> Result<Vec<Task>>{
+        let mut stmt = self.conn.prepare(&format!("SELECT data FROM {} ORDER BY rowid", self.table))?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+        let mut tasks = Vec::new();
+        for data in rows {
+            let data = data?;
+            let task: Task = serde_json::from_str(&data)?;
+            self.snapshots.read(task.uuid, &data);
+            tasks.push(task);
+        }
+
+        Ok(tasks)
+    }
+
+    fn insert(&mut self, task: &Task) -> Result<()>{
+        schema::check_writable(self.version)?;
+        let unique_ids = self.table == "tasks";
+        let tx = self.conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+        let taken: bool = tx.query_row(&format!("SELECT EXISTS (SELECT 1 FROM {} WHERE id = ?1)", self.table), params![task.id], |row| row.get(0))?;
+        if taken && unique_ids {
+            return Err(Error::DuplicateId(task.id));
+        }
+
+        let data = serde_json::to_string(task)?;
+        tx.execute(
+            &format!("INSERT INTO {} (uuid, id, data) VALUES (?1, ?2, ?3)", self.table),
+            params![task.uuid.to_string(), task.id, data],
+        )?;
+
+        tx.commit()?;
+        self.snapshots.wrote(task.uuid, &data);
+        Ok(())
+    }
+
+    fn update_many(&mut self, tasks: &[Task]) -> Result<()>{
+        schema::check_writable(self.version)?;
+        let unique_ids = self.table == "tasks";
+        let tx = self.conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let mut assigned = Vec::new();
+        let mut written = Vec::with_capacity(tasks.len());
+
+        for task in tasks {
+            let (stored_id, stored_data) = stored(&tx, self.table, &task.uuid)?;
+            if !self.snapshots.unchanged(&task.uuid, &stored_data) {
+                return Err(Error::Conflict(self.path.clone()));
+            }
+            if stored_id != task.id && unique_ids {
+                assigned.push(task.id);
+            }
+
+            let data = serde_json::to_string(task)?;
+            tx.execute(
+                &format!("UPDATE {} SET id = ?2, data = ?3 WHERE uuid = ?1", self.table),
+                params![task.uuid.to_string(), task.id, data],
+            )?;
+            written.push((task.uuid, data));
+        }
+
+        for id in assigned {
+            let count: i64 = tx.query_row(&format!("SELECT COUNT(*) FROM {} WHERE id = ?1", self.table), params![id], |row| row.get(0))?;
+            if count > 1 {
+                return Err(Error::DuplicateId(id));
+            }
+        }
+
+        tx.commit()?;
+        for (uuid, data) in written {
+            self.snapshots.wrote(uuid, &data);
+        }
+        Ok(())
+    }
+
+    fn delete(&mut self, uuid: &Uuid) -> Result<()>{
+        schema::check_writable(self.version)?;
+        let tx = self.conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+        let (_, stored_data) = stored(&tx, self.table, uuid)?;
+        if !self.snapshots.unchanged(uuid, &stored_data) {
+            return Err(Error::Conflict(self.path.clone()));
+        }
+        tx.execute(&format!("DELETE FROM {} WHERE uuid = ?1", self.table), params![uuid.to_string()])?;
+
+        Ok(tx.commit()?)
+    }
+
+    fn begin_operation(&mut self, _action: Action){
+        self.snapshots.clear();
+    }
+
+    fn record(&mut self, events: &[Event]) -> Result<()>{
+        let tx = self.conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        for event in events {
+            tx.execute("INSERT INTO activity (data) VALUES (?1)", params![history::encode(event)?])?;
+        }
+
+        Ok(tx.commit()?)
+    }
+
+    fn events(&mut self) -> Result<Vec<Event>>{
+        let mut stmt = self.conn.prepare("SELECT data FROM activity ORDER BY seq")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+        let mut events = Vec::new();
+        for data in rows {
+            events.push(history::decode(&data?)?);
+        }
+
+        Ok(events)
+    }
+}
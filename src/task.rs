@@ -0,0 +1,515 @@
+use serde::{
+    Serialize,
+    Deserialize
+};
+use chrono::{
+    DateTime,
+    NaiveDate,
+    Utc
+};
+use std::{
+    collections::BTreeSet,
+    fmt,
+    str::FromStr
+};
+use uuid::Uuid;
+
+use crate::{
+    due::{
+        self,
+        DueTime
+    },
+    error::{
+        Error,
+        Result
+    },
+    estimate::{
+        self,
+        Estimate
+    },
+    project,
+    recur::{
+        self,
+        Recurrence
+    },
+    worklog::Work
+};
+
+/// Where a task is in its workflow. Besides the built-in statuses, users can
+/// define their own in `config.json`; those are always open.
+#[derive(Debug,Clone,Serialize,Deserialize,PartialEq,Eq,Hash)]
+#[serde(from = "String", into = "String")]
+pub enum Status{
+    Completed,
+    NotCompleted,
+    InProgress,
+    Blocked,
+    InReview,
+    Cancelled,
+    Custom(String),
+}
+
+impl Status{
+    pub const BUILT_IN: [Status; 6] = [Status::NotCompleted, Status::InProgress, Status::Blocked, Status::InReview, Status::Completed, Status::Cancelled];
+
+    /// Completed and cancelled tasks are closed, every other status is open.
+    pub fn is_open(&self) -> bool{
+        !matches!(self, Status::Completed | Status::Cancelled)
+    }
+
+    /// The name the status is stored under, e.g. `InProgress`.
+    pub fn name(&self) -> &str{
+        match self {
+            Status::Completed       => "Completed",
+            Status::NotCompleted    => "NotCompleted",
+            Status::InProgress      => "InProgress",
+            Status::Blocked         => "Blocked",
+            Status::InReview        => "InReview",
+            Status::Cancelled       => "Cancelled",
+            Status::Custom(name)    => name,
+        }
+    }
+}
+
+impl std::fmt::Display for Status{
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        match self{
+            Status::Completed       => write!(f, "Completed"),
+            Status::NotCompleted    => write!(f, "Not Completed"),
+            Status::InProgress      => write!(f, "In Progress"),
+            Status::Blocked         => write!(f, "Blocked"),
+            Status::InReview        => write!(f, "In Review"),
+            Status::Cancelled       => write!(f, "Cancelled"),
+            Status::Custom(name)    => write!(f, "{}", name),
+        }
+    }
+}
+
+impl FromStr for Status {
+    type Err = ();
+
+    /// Parses a built-in status, ignoring case, spaces, `-` and `_`.
+    fn from_str(input: &str) -> std::result::Result<Status, Self::Err>{
+        let key: String = input.chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '_'))
+            .collect::<String>()
+            .to_lowercase();
+
+        Status::BUILT_IN.into_iter()
+            .find(|status| status.name().to_lowercase() == key)
+            .ok_or(())
+    }
+}
+
+impl From<String> for Status{
+    fn from(name: String) -> Status{
+        Status::from_str(&name).unwrap_or(Status::Custom(name))
+    }
+}
+
+impl From<Status> for String{
+    fn from(status: Status) -> String{
+        status.name().to_string()
+    }
+}
+
+/// A change of a task's status.
+#[derive(Debug,Clone,Serialize,Deserialize)]
+pub struct Transition{
+    pub from:   Status,
+    pub to:     Status,
+    pub at:     DateTime<Utc>,
+}
+
+/// A message in a task's comment thread.
+#[derive(Debug,Clone,Serialize,Deserialize)]
+pub struct Comment{
+    pub author: String,
+    pub at:     DateTime<Utc>,
+    pub text:   String,
+}
+
+#[derive(Debug,Clone,Serialize,Deserialize,PartialEq)]
+pub enum Priority {
+    Critical,
+    High,
+    Medium,
+    Low,
+}
+
+impl FromStr for Priority {
+    type Err = ();
+
+    fn from_str(input: &str) -> std::result::Result<Priority, Self::Err>{
+        match input.to_lowercase().as_str(){
+            "critical"  => Ok(Priority::Critical),
+            "high"      => Ok(Priority::High),
+            "medium"    => Ok(Priority::Medium),
+            "low"       => Ok(Priority::Low),
+            _           => Err(()),
+        }
+    }
+}
+
+impl Priority{
+    /// How urgent the priority is, from 1 for `Low` to 4 for `Critical`.
+    pub fn level(&self) -> u8{
+        match *self{
+            Priority::Critical  => 4,
+            Priority::High      => 3,
+            Priority::Medium    => 2,
+            Priority::Low       => 1,
+        }
+    }
+}
+
+impl std::fmt::Display for Priority{
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        match *self{
+            Priority::Critical  => write!(f, "Critical"),
+            Priority::High      => write!(f, "High"),
+            Priority::Medium    => write!(f, "Medium"),
+            Priority::Low       => write!(f, "Low"),
+        }
+    }
+}
+
+/// A task field that can be edited or searched by name.
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum Field{
+    Id,
+    Priority,
+    Title,
+    Description,
+    DueDate,
+    DueTime,
+    Status,
+    Assignees,
+    Parent,
+    BlockedBy,
+    Tags,
+    Recurrence,
+    Estimate,
+}
+
+impl Field{
+    pub const NAMES: &'static str = "id, priority, title, description, due_date, due_time, status, assignees, parent, blocked_by, tags, recurrence, estimate";
+}
+
+impl FromStr for Field {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Field>{
+        match input.trim(){
+            "id"            => Ok(Field::Id),
+            "priority"      => Ok(Field::Priority),
+            "title"         => Ok(Field::Title),
+            "description"   => Ok(Field::Description),
+            "due_date"      => Ok(Field::DueDate),
+            "due_time"      => Ok(Field::DueTime),
+            "status"        => Ok(Field::Status),
+            "assignees"     => Ok(Field::Assignees),
+            "parent"        => Ok(Field::Parent),
+            "blocked_by"    => Ok(Field::BlockedBy),
+            "tags"          => Ok(Field::Tags),
+            "recurrence"    => Ok(Field::Recurrence),
+            "estimate"      => Ok(Field::Estimate),
+            _               => Err(Error::Invalid(format!("Invalid field: {}, expected one of: {}", input, Field::NAMES))),
+        }
+    }
+}
+
+impl std::fmt::Display for Field{
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        match *self{
+            Field::Id           => write!(f, "id"),
+            Field::Priority     => write!(f, "priority"),
+            Field::Title        => write!(f, "title"),
+            Field::Description  => write!(f, "description"),
+            Field::DueDate      => write!(f, "due_date"),
+            Field::DueTime      => write!(f, "due_time"),
+            Field::Status       => write!(f, "status"),
+            Field::Assignees    => write!(f, "assignees"),
+            Field::Parent       => write!(f, "parent"),
+            Field::BlockedBy    => write!(f, "blocked_by"),
+            Field::Tags         => write!(f, "tags"),
+            Field::Recurrence   => write!(f, "recurrence"),
+            Field::Estimate     => write!(f, "estimate"),
+        }
+    }
+}
+
+/// A task ID as it was given: a number, or a number with a prefix, e.g. `OPS-42`.
+/// The prefix has to be the one of the task's project, see `commands::resolve_key`.
+#[derive(Debug,Clone,PartialEq)]
+pub struct Key{
+    pub prefix: Option<String>,
+    pub id:     i32,
+}
+
+/// Parses a task ID given either as a number or with a prefix, e.g. `OPS-42`.
+/// IDs start at 1.
+pub fn parse_key(input: &str) -> Result<Key>{
+    let input = input.trim();
+    let (prefix, number) = match input.rsplit_once('-') {
+        Some((prefix, number)) if !prefix.is_empty() && prefix.chars().all(|c| c.is_ascii_alphanumeric()) => (Some(prefix.to_string()), number),
+        _ => (None, input),
+    };
+    let id = number.parse().ok()
+        .filter(|id| *id > 0)
+        .ok_or_else(|| Error::Invalid(format!("Invalid ID: {}, expected a number of 1 or more", input)))?;
+
+    Ok(Key{ prefix, id })
+}
+
+/// Parses a task ID, ignoring its prefix if it has one.
+pub fn parse_id(input: &str) -> Result<i32>{
+    Ok(parse_key(input)?.id)
+}
+
+pub fn parse_priority(input: &str) -> Result<Priority>{
+    Priority::from_str(input.trim()).map_err(|_| Error::Invalid(format!("Invalid priority: {}, expected Critical, High, Medium or Low", input)))
+}
+
+/// Parses a built-in status, or takes any other name as a custom status.
+/// Whether a custom status exists is up to the configuration.
+pub fn parse_status(input: &str) -> Result<Status>{
+    match input.trim() {
+        ""      => Err(Error::Invalid("The status cannot be empty".to_string())),
+        name    => Ok(Status::from(name.to_string())),
+    }
+}
+
+/// Parses an ISO date or a date relative to today such as `tomorrow` or `+3d`.
+pub fn parse_due_date(input: &str) -> Result<NaiveDate>{
+    due::parse_date(input, due::today())
+}
+
+/// Parses a parent task UUID, or `none` (or nothing) for a top-level task.
+pub fn parse_parent(input: &str) -> Result<Option<Uuid>>{
+    match input.trim() {
+        "" | "none" => Ok(None),
+        uuid        => Uuid::parse_str(uuid).map(Some).map_err(|_| Error::Invalid(format!("Invalid parent: {}, expected a task UUID or none", input))),
+    }
+}
+
+/// Parses comma separated blocker task UUIDs, or `none` (or nothing) for none.
+pub fn parse_blockers(input: &str) -> Result<Vec<Uuid>>{
+    input.split(',')
+        .map(str::trim)
+        .filter(|uuid| !uuid.is_empty() && *uuid != "none")
+        .map(|uuid| Uuid::parse_str(uuid).map_err(|_| Error::Invalid(format!("Invalid blocker: {}, expected a task UUID", uuid))))
+        .collect()
+}
+
+/// Normalises a tag: no leading `#`, lowercase, and no spaces or commas.
+pub fn parse_tag(input: &str) -> Result<String>{
+    let tag = input.trim().trim_start_matches('#').to_lowercase();
+
+    if tag.is_empty() || tag.contains(|c: char| c.is_whitespace() || c == ',') {
+        return Err(Error::Invalid(format!("Invalid tag: {}, tags cannot be empty or contain spaces or commas", input)));
+    }
+
+    Ok(tag)
+}
+
+/// Parses comma separated tags; `none` (or nothing) is no tags.
+pub fn parse_tags(input: &str) -> Result<BTreeSet<String>>{
+    input.split(',')
+        .filter(|tag| !tag.trim().is_empty() && tag.trim() != "none")
+        .map(parse_tag)
+        .collect()
+}
+
+pub fn parse_title(input: &str) -> Result<String>{
+    match input.trim() {
+        ""      => Err(Error::Invalid("The title cannot be empty".to_string())),
+        title   => Ok(title.to_string()),
+    }
+}
+
+/// Splits a comma separated list of names, requiring at least one.
+pub fn parse_assignees(input: &str) -> Result<Vec<String>>{
+    let assignees: Vec<String> = input.split(',')
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect();
+
+    if assignees.is_empty() {
+        return Err(Error::Invalid("Enter at least 1 assignee name".to_string()));
+    }
+
+    Ok(assignees)
+}
+
+#[derive(Debug,Clone,Serialize,Deserialize)]
+pub struct Task{
+    pub uuid:           Uuid,
+    pub id:             i32,
+    pub priority:       Priority,
+    pub title:          String,
+    pub description:    String,
+    /// The date the task is due, in the zone of its due time when it has one.
+    pub due_date:       NaiveDate,
+    /// The time of day the task is due, if not just some time on its due date.
+    pub due_time:       Option<DueTime>,
+    pub status:         Status,
+    pub assignees:      Vec<String>,
+    /// The task this is a subtask of.
+    pub parent:         Option<Uuid>,
+    /// Tasks that have to be completed before this one.
+    pub blocked_by:     Vec<Uuid>,
+    pub tags:           BTreeSet<String>,
+    pub recurrence:     Option<Recurrence>,
+    /// The first task of the series a recurring task's occurrences belong to.
+    pub series:         Option<Uuid>,
+    /// Every status change, oldest first.
+    pub status_history: Vec<Transition>,
+    /// Time spent on the task, including running timers.
+    pub worklog:        Vec<Work>,
+    pub estimate:       Option<Estimate>,
+    /// The conversation about the task, oldest first.
+    pub comments:       Vec<Comment>,
+    /// The project the task belongs to.
+    pub project:        String,
+    /// When the task was moved to the trash; only set on tasks in the trash.
+    pub deleted_at:     Option<DateTime<Utc>>,
+    /// When the task was moved to the archive; only set on archived tasks.
+    pub archived_at:    Option<DateTime<Utc>>,
+}
+
+impl Task{
+    /// Creates a task without an ID; one is allocated when it is added to the store.
+    pub fn new(priority: Priority, title: String, description: String, due_date: NaiveDate, assignees: Vec<String>) -> Task{
+        Task{
+            uuid:           Uuid::new_v4(),
+            id:             0,
+            priority,
+            title,
+            description,
+            due_date,
+            due_time:       None,
+            status:         Status::NotCompleted,
+            assignees,
+            parent:         None,
+            blocked_by:     Vec::new(),
+            tags:           BTreeSet::new(),
+            recurrence:     None,
+            series:         None,
+            status_history: Vec::new(),
+            worklog:        Vec::new(),
+            estimate:       None,
+            comments:       Vec::new(),
+            project:        project::DEFAULT.to_string(),
+            deleted_at:     None,
+            archived_at:    None,
+        }
+    }
+
+    /// The next occurrence of a recurring task completed on `completed`: a new,
+    /// open task due on the next date of the series. It gets its ID when added.
+    pub fn next_occurrence(&self, completed: NaiveDate) -> Option<Task>{
+        let recurrence = self.recurrence.as_ref()?;
+        let due_date = recurrence.next(self.due_date, completed);
+
+        Some(Task{
+            uuid:           Uuid::new_v4(),
+            id:             0,
+            due_date,
+            due_time:       self.due_time.as_ref().map(|time| time.on(due_date)),
+            status:         Status::NotCompleted,
+            blocked_by:     Vec::new(),
+            series:         Some(self.series.unwrap_or(self.uuid)),
+            status_history: Vec::new(),
+            worklog:        Vec::new(),
+            comments:       Vec::new(),
+            ..self.clone()
+        })
+    }
+
+    /// When the task is due: its due time in the viewer's zone, or just its due date.
+    pub fn due(&self) -> String{
+        match &self.due_time {
+            Some(time)  => time.to_string(),
+            None        => self.due_date.to_string(),
+        }
+    }
+
+    /// The timers running on the task.
+    pub fn running_timers(&self) -> Vec<&Work>{
+        self.worklog.iter().filter(|work| work.is_running()).collect()
+    }
+
+    /// Changes the status, recording the change with the current time.
+    pub fn set_status(&mut self, status: Status){
+        if status != self.status {
+            self.status_history.push(Transition{ from: self.status.clone(), to: status.clone(), at: Utc::now() });
+            self.status = status;
+        }
+    }
+
+    /// Parses `value` for `field` and stores it, leaving the task unchanged when
+    /// the value is invalid.
+    pub fn set(&mut self, field: Field, value: &str) -> Result<()>{
+        match field {
+            Field::Id           => self.id = parse_id(value)?,
+            Field::Priority     => self.priority = parse_priority(value)?,
+            Field::Title        => self.title = parse_title(value)?,
+            Field::Description  => self.description = value.trim().to_string(),
+            Field::DueDate      => {
+                self.due_date = parse_due_date(value)?;
+                // Keep the time of day, now on the new date.
+                self.due_time = self.due_time.as_ref().map(|time| time.on(self.due_date));
+            }
+            Field::DueTime      => {
+                self.due_time = due::parse(value, self.due_date)?;
+                // A time given in another zone can fall on another date there.
+                if let Some(time) = &self.due_time {
+                    self.due_date = time.date();
+                }
+            }
+            Field::Status       => self.set_status(parse_status(value)?),
+            Field::Assignees    => self.assignees = parse_assignees(value)?,
+            Field::Parent       => self.parent = parse_parent(value)?,
+            Field::BlockedBy    => {
+                let blocked_by = parse_blockers(value)?;
+                if blocked_by.contains(&self.uuid) {
+                    return Err(Error::Invalid("A task cannot block itself".to_string()));
+                }
+                self.blocked_by = blocked_by;
+            }
+            Field::Tags         => self.tags = parse_tags(value)?,
+            Field::Recurrence   => self.recurrence = recur::parse(value)?,
+            Field::Estimate     => self.estimate = estimate::parse(value)?,
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_with_and_without_prefixes(){
+        assert_eq!(parse_key(" 42 ").unwrap(), Key{ prefix: None, id: 42 });
+        assert_eq!(parse_key("OPS-42").unwrap(), Key{ prefix: Some("OPS".to_string()), id: 42 });
+        assert_eq!(parse_id("web2-7").unwrap(), 7);
+    }
+
+    #[test]
+    fn ids_start_at_one(){
+        for input in ["0", "-7", "OPS-0", "OPS--3", "", "four", "OPS-"] {
+            assert!(parse_key(input).is_err(), "{} was accepted", input);
+        }
+
+        let mut task = Task::new(Priority::Low, "Task".to_string(), String::new(), "2026-10-20".parse().unwrap(), Vec::new());
+        task.id = 1;
+        assert!(task.set(Field::Id, "-7").is_err());
+        assert_eq!(task.id, 1);
+    }
+}
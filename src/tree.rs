@@ -0,0 +1,178 @@
+use std::collections::{
+    HashMap,
+    HashSet
+};
+use uuid::Uuid;
+
+use crate::task::{
+    Task,
+    Status
+};
+
+/// The UUIDs of every task below `root`, children before grandchildren.
+pub fn descendants(tasks: &[Task], root: Uuid) -> Vec<Uuid>{
+    let mut found = vec![root];
+    let mut index = 0;
+
+    while index < found.len() {
+        let parent = found[index];
+        for task in tasks {
+            if task.parent == Some(parent) && !found.contains(&task.uuid) {
+                found.push(task.uuid);
+            }
+        }
+        index += 1;
+    }
+
+    found.remove(0);
+    found
+}
+
+/// `(completed, total)` direct subtasks of every task that has any. Cancelled
+/// subtasks are left out.
+pub fn progress(tasks: &[Task]) -> HashMap<Uuid, (usize, usize)>{
+    let mut progress: HashMap<Uuid, (usize, usize)> = HashMap::new();
+
+    for task in tasks.iter().filter(|t| t.status != Status::Cancelled) {
+        if let Some(parent) = task.parent {
+            let (done, total) = progress.entry(parent).or_default();
+            if task.status == Status::Completed {
+                *done += 1;
+            }
+            *total += 1;
+        }
+    }
+
+    progress
+}
+
+/// Returns a task that is its own ancestor, if the parents form a cycle.
+pub fn find_cycle(tasks: &[Task]) -> Option<&Task>{
+    let parents: HashMap<Uuid, Uuid> = tasks.iter()
+        .filter_map(|task| Some((task.uuid, task.parent?)))
+        .collect();
+
+    tasks.iter().find(|task| {
+        let mut current = task.uuid;
+        // A chain longer than the number of tasks must revisit one of them.
+        for _ in 0..=parents.len() {
+            match parents.get(&current) {
+                Some(parent) if *parent == task.uuid    => return true,
+                Some(parent)                            => current = *parent,
+                None                                    => return false,
+            }
+        }
+        false
+    })
+}
+
+/// Orders tasks depth first so every subtask follows its parent, keeping the
+/// existing order among siblings. Each task comes with the tree lines to draw
+/// before its title. Tasks whose parent is not among `tasks` are roots.
+pub fn arrange(tasks: Vec<Task>) -> Vec<(String, Task)>{
+    let present: HashSet<Uuid> = tasks.iter().map(|t| t.uuid).collect();
+    let mut children: HashMap<Uuid, Vec<usize>> = HashMap::new();
+    let mut roots = Vec::new();
+
+    for (index, task) in tasks.iter().enumerate() {
+        match task.parent.filter(|parent| present.contains(parent) && *parent != task.uuid) {
+            Some(parent)    => children.entry(parent).or_default().push(index),
+            None            => roots.push(index),
+        }
+    }
+
+    fn visit(index: usize, prefix: String, lead: String, tasks: &[Task], children: &HashMap<Uuid, Vec<usize>>, order: &mut Vec<(String, usize)>, seen: &mut HashSet<usize>){
+        if !seen.insert(index) {
+            return;
+        }
+        order.push((prefix, index));
+
+        let kids = children.get(&tasks[index].uuid).map(Vec::as_slice).unwrap_or_default();
+        for (position, kid) in kids.iter().enumerate() {
+            let last = position + 1 == kids.len();
+            let branch = if last { "└─ " } else { "├─ " };
+            let indent = if last { "   " } else { "│  " };
+            visit(*kid, format!("{}{}", lead, branch), format!("{}{}", lead, indent), tasks, children, order, seen);
+        }
+    }
+
+    let mut order = Vec::with_capacity(tasks.len());
+    let mut seen = HashSet::new();
+    for root in roots {
+        visit(root, String::new(), String::new(), &tasks, &children, &mut order, &mut seen);
+    }
+    // Tasks caught in a parent cycle are never reached from a root; list them flat.
+    for index in 0..tasks.len() {
+        visit(index, String::new(), String::new(), &tasks, &children, &mut order, &mut seen);
+    }
+
+    let mut tasks: Vec<Option<Task>> = tasks.into_iter().map(Some).collect();
+    order.into_iter()
+        .filter_map(|(prefix, index)| Some((prefix, tasks[index].take()?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Priority;
+
+    fn task(id: i32, status: Status) -> Task{
+        let mut task = Task::new(Priority::Medium, format!("Task {}", id), String::new(), "2026-10-20".parse().unwrap(), Vec::new());
+        task.id = id;
+        task.status = status;
+        task
+    }
+
+    /// 1 with subtasks 2 and 3, 3 with subtask 4, and 5 on its own.
+    fn family() -> Vec<Task>{
+        let mut tasks: Vec<Task> = [Status::NotCompleted, Status::Completed, Status::InProgress, Status::Cancelled, Status::NotCompleted]
+            .into_iter()
+            .zip(1..)
+            .map(|(status, id)| task(id, status))
+            .collect();
+        tasks[1].parent = Some(tasks[0].uuid);
+        tasks[2].parent = Some(tasks[0].uuid);
+        tasks[3].parent = Some(tasks[2].uuid);
+        tasks
+    }
+
+    #[test]
+    fn descendants_include_grandchildren(){
+        let tasks = family();
+        assert_eq!(descendants(&tasks, tasks[0].uuid), [tasks[1].uuid, tasks[2].uuid, tasks[3].uuid]);
+        assert!(descendants(&tasks, tasks[4].uuid).is_empty());
+    }
+
+    #[test]
+    fn progress_counts_direct_subtasks_except_cancelled(){
+        let tasks = family();
+        let progress = progress(&tasks);
+        assert_eq!(progress.get(&tasks[0].uuid), Some(&(1, 2)));
+        assert_eq!(progress.get(&tasks[2].uuid), None);
+    }
+
+    #[test]
+    fn subtasks_follow_their_parent(){
+        let mut tasks = family();
+        tasks.reverse();
+        let arranged: Vec<(String, i32)> = arrange(tasks).into_iter().map(|(prefix, task)| (prefix, task.id)).collect();
+        assert_eq!(arranged, [
+            (String::new(), 5),
+            (String::new(), 1),
+            ("├─ ".to_string(), 3),
+            ("│  └─ ".to_string(), 4),
+            ("└─ ".to_string(), 2),
+        ]);
+    }
+
+    #[test]
+    fn parent_cycles_are_found_and_still_listed(){
+        let mut tasks = family();
+        assert!(find_cycle(&tasks).is_none());
+
+        tasks[0].parent = Some(tasks[3].uuid);
+        assert_eq!(find_cycle(&tasks).map(|t| t.id), Some(1));
+        assert_eq!(arrange(tasks).len(), 5);
+    }
+}
@@ -0,0 +1,687 @@
+use chrono::{
+    Local,
+    Utc
+};
+use clap::ValueEnum;
+use owo_colors::{
+    OwoColorize,
+    colors::css::{
+        LimeGreen,
+        Red,
+        Orange,
+        Yellow,
+        Aqua,
+        White,
+    }
+};
+use serde::{
+    Serialize,
+    Deserialize
+};
+use std::{
+    cmp::Ordering,
+    collections::HashMap
+};
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    deps,
+    history::{
+        Entry,
+        Operation
+    },
+    task::{
+        Task,
+        Status,
+        Priority
+    },
+    tree,
+    worklog
+};
+
+/// Width used for rulers and tables when the terminal size is unknown, e.g. when piped.
+const DEFAULT_WIDTH: usize = 110;
+
+#[derive(Debug,Clone,Copy,PartialEq,Serialize,Deserialize,ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum SortKey{
+    /// Most urgent first
+    Priority,
+    /// Earliest due date first
+    Due,
+    /// Open tasks first
+    Status,
+    Id,
+}
+
+#[derive(Debug,Clone,Copy,PartialEq,Serialize,Deserialize,ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy{
+    /// One group per assignee; tasks with several assignees appear in each
+    Assignee,
+    /// One group per tag; tasks with several tags appear in each
+    Tag,
+    Status,
+    Priority,
+    Project,
+}
+
+#[derive(Debug,Clone,Copy,PartialEq,Serialize,Deserialize,ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Column{
+    Id,
+    Uuid,
+    Priority,
+    Status,
+    /// Completed out of all subtasks, e.g. 3/5
+    Progress,
+    Due,
+    Title,
+    Description,
+    Assignees,
+    Tags,
+    /// How a recurring task repeats
+    Repeat,
+    /// Open tasks blocking it
+    #[serde(rename = "blocked-by")]
+    BlockedBy,
+    /// Hours or story points
+    Estimate,
+    /// Number of comments
+    Comments,
+    /// Time logged, marked with ▶ while a timer is running
+    Time,
+    Project,
+}
+
+/// A task as it appears in a list, with what the list knows about its subtasks.
+struct Row{
+    task:       Task,
+    /// Tree lines drawn before the title of a subtask.
+    prefix:     String,
+    progress:   Option<(usize, usize)>,
+    parent:     Option<String>,
+    /// The first task of a recurring task's series.
+    series:     Option<String>,
+    /// Keys of the open tasks blocking this one.
+    blockers:   Vec<String>,
+    /// Keys of the open tasks this one blocks.
+    blocks:     Vec<String>,
+}
+
+impl Row{
+    fn progress(&self) -> String{
+        self.progress
+            .map(|(done, total)| format!("{}/{}", done, total))
+            .unwrap_or_default()
+    }
+}
+
+impl Column{
+    fn header(&self) -> &'static str{
+        match self {
+            Column::Id          => "ID",
+            Column::Uuid        => "UUID",
+            Column::Priority    => "Priority",
+            Column::Status      => "Status",
+            Column::Progress    => "Progress",
+            Column::Due         => "Due",
+            Column::Title       => "Title",
+            Column::Description => "Description",
+            Column::Assignees   => "Assignees",
+            Column::Tags        => "Tags",
+            Column::Repeat      => "Repeat",
+            Column::BlockedBy   => "Blocked By",
+            Column::Estimate    => "Estimate",
+            Column::Comments    => "Comments",
+            Column::Time        => "Time",
+            Column::Project     => "Project",
+        }
+    }
+
+    /// Free-text columns are truncated when the table is wider than the terminal.
+    fn shrinkable(&self) -> bool{
+        matches!(self, Column::Title | Column::Description | Column::Assignees | Column::Tags)
+    }
+
+    fn text(&self, config: &Config, row: &Row) -> String{
+        let task = &row.task;
+        match self {
+            Column::Id          => config.key(task),
+            Column::Uuid        => task.uuid.to_string(),
+            Column::Priority    => task.priority.to_string(),
+            Column::Status      => status(task),
+            Column::Progress    => row.progress(),
+            Column::Due         => task.due(),
+            Column::Title       => format!("{}{}", row.prefix, task.title),
+            Column::Description => task.description.replace('\n', " "),
+            Column::Assignees   => task.assignees.join(", "),
+            Column::Tags        => task.tags.iter().cloned().collect::<Vec<_>>().join(", "),
+            Column::Repeat      => task.recurrence.as_ref().map(|r| r.to_string()).unwrap_or_default(),
+            Column::BlockedBy   => row.blockers.join(", "),
+            Column::Estimate    => task.estimate.map(|estimate| estimate.to_string()).unwrap_or_default(),
+            Column::Project     => task.project.clone(),
+            Column::Comments    => match task.comments.len() {
+                0       => String::new(),
+                count   => count.to_string(),
+            },
+            Column::Time        => match (task.worklog.is_empty(), task.running_timers().is_empty()) {
+                (true, _)       => String::new(),
+                (false, true)   => worklog::format_duration(worklog::total(&task.worklog, Utc::now())),
+                (false, false)  => format!("▶ {}", worklog::format_duration(worklog::total(&task.worklog, Utc::now()))),
+            },
+        }
+    }
+}
+
+/// How `list` orders, groups and lays out tasks. The defaults come from the
+/// `list` section of `config.json` and can be overridden per command.
+#[derive(Debug,Clone,Serialize,Deserialize)]
+#[serde(default)]
+pub struct ListOptions{
+    /// Sort keys in order of importance; ties keep the stored order.
+    pub sort:       Vec<SortKey>,
+    pub reverse:    bool,
+    pub group:      Option<GroupBy>,
+    /// One line per task instead of a block of fields.
+    pub table:      bool,
+    pub columns:    Vec<Column>,
+    /// List subtasks under their parents rather than in sort order.
+    pub tree:       bool,
+}
+
+impl Default for ListOptions{
+    fn default() -> ListOptions{
+        ListOptions{
+            sort:       Vec::new(),
+            reverse:    false,
+            group:      None,
+            table:      false,
+            columns:    vec![Column::Id, Column::Priority, Column::Status, Column::Progress, Column::Due, Column::Title, Column::Assignees, Column::Tags, Column::Estimate, Column::Time, Column::Comments],
+            tree:       true,
+        }
+    }
+}
+
+/// A task's status, noting when the task is archived.
+fn status(task: &Task) -> String{
+    match task.archived_at {
+        Some(_) => format!("{} (archived)", task.status),
+        None    => task.status.to_string(),
+    }
+}
+
+/// Orders statuses from not started, through work in progress, to closed.
+fn status_rank(status: &Status) -> u8{
+    match status {
+        Status::NotCompleted    => 0,
+        Status::InProgress      => 1,
+        Status::Custom(_)       => 2,
+        Status::InReview        => 3,
+        Status::Blocked         => 4,
+        Status::Completed       => 5,
+        Status::Cancelled       => 6,
+    }
+}
+
+fn compare(a: &Task, b: &Task, key: SortKey) -> Ordering{
+    match key {
+        SortKey::Priority   => b.priority.level().cmp(&a.priority.level()),
+        // On the same date, tasks due at a set time come before those due any time that day.
+        SortKey::Due        => a.due_date.cmp(&b.due_date)
+            .then_with(|| match (&a.due_time, &b.due_time) {
+                (Some(a), Some(b))  => a.at.cmp(&b.at),
+                (Some(_), None)     => Ordering::Less,
+                (None, Some(_))     => Ordering::Greater,
+                (None, None)        => Ordering::Equal,
+            }),
+        SortKey::Status     => status_rank(&a.status).cmp(&status_rank(&b.status)),
+        SortKey::Id         => a.id.cmp(&b.id),
+    }
+}
+
+pub fn sort(tasks: &mut [Task], keys: &[SortKey], reverse: bool){
+    tasks.sort_by(|a, b| {
+        let ordering = keys.iter().fold(Ordering::Equal, |ordering, key| ordering.then_with(|| compare(a, b, *key)));
+        if reverse { ordering.reverse() } else { ordering }
+    });
+}
+
+/// Splits tasks into titled groups, keeping the order of tasks within each group.
+pub fn group(tasks: Vec<Task>, by: GroupBy) -> Vec<(String, Vec<Task>)>{
+    let mut groups: Vec<(String, Vec<Task>)> = Vec::new();
+    let mut push = |name: String, task: Task| {
+        match groups.iter_mut().find(|(group, _)| *group == name) {
+            Some((_, members))  => members.push(task),
+            None                => groups.push((name, vec![task])),
+        }
+    };
+
+    for task in tasks {
+        match by {
+            GroupBy::Status     => push(task.status.to_string(), task),
+            GroupBy::Priority   => push(task.priority.to_string(), task),
+            GroupBy::Project    => push(task.project.clone(), task),
+            GroupBy::Assignee   => {
+                if task.assignees.is_empty() {
+                    push("(unassigned)".to_string(), task);
+                } else {
+                    for assignee in task.assignees.clone() {
+                        push(assignee, task.clone());
+                    }
+                }
+            }
+            GroupBy::Tag        => {
+                if task.tags.is_empty() {
+                    push("(untagged)".to_string(), task);
+                } else {
+                    for tag in task.tags.clone() {
+                        push(tag, task.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    match by {
+        GroupBy::Assignee
+        | GroupBy::Tag
+        | GroupBy::Project  => groups.sort_by_key(|(name, _)| name.to_lowercase()),
+        GroupBy::Status     => groups.sort_by_key(|(_, members)| status_rank(&members[0].status)),
+        GroupBy::Priority   => groups.sort_by_key(|(_, members)| std::cmp::Reverse(members[0].priority.level())),
+    }
+
+    groups
+}
+
+/// The terminal's width, or `$COLUMNS` when output is not a terminal.
+pub fn terminal_width() -> usize{
+    terminal_size::terminal_size()
+        .map(|(width, _)| width.0 as usize)
+        .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+pub fn colored_priority(priority: &Priority) -> String{
+    match priority {
+        Priority::Critical  => r#"Critical"#.bold().fg::<Red>().to_string(),
+        Priority::High      => r#"High"#.bold().fg::<Orange>().to_string(),
+        Priority::Medium    => r#"Medium"#.bold().fg::<Yellow>().to_string(),
+        Priority::Low       => r#"Low"#.bold().fg::<LimeGreen>().to_string(),
+    }
+}
+
+/// Renders a tag in its configured colour, if it has one.
+pub fn colored_tag(config: &Config, tag: &str) -> String{
+    match config.tag_color(tag) {
+        Some(color) => tag.color(color).to_string(),
+        None        => tag.to_string(),
+    }
+}
+
+pub fn colored_tags<'a>(config: &Config, tags: impl IntoIterator<Item = &'a String>) -> String{
+    tags.into_iter()
+        .map(|tag| colored_tag(config, tag))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Prints each task as a block of labelled fields between rulers.
+fn print_blocks(config: &Config, rows: &[Row]){
+    let ruler = "-".repeat(terminal_width().min(DEFAULT_WIDTH));
+
+    println!("{}", ruler);
+    for row in rows {
+        let task = &row.task;
+        println!("{}: {}",r#"ID"#.underline().bold().fg::<Aqua>() ,config.key(task).bold().fg::<White>());
+        println!("{} : {}",r#"UUID"#.underline().bold().fg::<Aqua>(),task.uuid);
+        println!("{}: {}",r#"Priority"#.underline().bold().fg::<Aqua>(),colored_priority(&task.priority));
+        println!("{}: {}",r#"Title"#.underline().bold().fg::<Aqua>() , task.title);
+        println!("{}: {}",r#"Project"#.underline().bold().fg::<Aqua>() , task.project);
+        println!("{}: {}",r#"Description"#.underline().bold().fg::<Aqua>() ,task.description);
+        println!("{}: {}",r#"Due Date"#.underline().bold().fg::<Aqua>() , task.due());
+        println!("{}: {}",r#"Status"#.underline().bold().fg::<Aqua>(), status(task).bold());
+        for transition in &task.status_history {
+            println!("    {}  {} -> {}", transition.at.with_timezone(&Local).format("%Y-%m-%d %H:%M"), transition.from, transition.to);
+        }
+        println!("{}: {}",r#"Assignees"#.underline().bold().fg::<Aqua>(), task.assignees.join(", "));
+        if !task.tags.is_empty() {
+            println!("{}: {}",r#"Tags"#.underline().bold().fg::<Aqua>(), colored_tags(config, &task.tags));
+        }
+        if let Some(recurrence) = &task.recurrence {
+            println!("{}: {}",r#"Repeats"#.underline().bold().fg::<Aqua>(), recurrence);
+        }
+        if let Some(series) = &row.series {
+            println!("{}: {}",r#"Series"#.underline().bold().fg::<Aqua>(), series);
+        }
+        if let Some(parent) = &row.parent {
+            println!("{}: {}",r#"Parent"#.underline().bold().fg::<Aqua>(), parent);
+        }
+        if !row.blockers.is_empty() {
+            println!("{}: {}",r#"Blocked By"#.underline().bold().fg::<Aqua>(), row.blockers.join(", "));
+        }
+        if !row.blocks.is_empty() {
+            println!("{}: {}",r#"Blocks"#.underline().bold().fg::<Aqua>(), row.blocks.join(", "));
+        }
+        if row.progress.is_some() {
+            println!("{}: {} completed",r#"Subtasks"#.underline().bold().fg::<Aqua>(), row.progress());
+        }
+        if !task.comments.is_empty() {
+            let last = task.comments.last().map(|comment| comment.at.with_timezone(&Local).format("%Y-%m-%d %H:%M"));
+            println!("{}: {} (latest {})",r#"Comments"#.underline().bold().fg::<Aqua>(), task.comments.len(), last.map(|at| at.to_string()).unwrap_or_default());
+        }
+        if let Some(estimate) = &task.estimate {
+            println!("{}: {}",r#"Estimate"#.underline().bold().fg::<Aqua>(), estimate);
+        }
+        if !task.worklog.is_empty() {
+            let now = Utc::now();
+            let by_assignee: Vec<String> = worklog::by_assignee(&task.worklog, now).into_iter()
+                .map(|(assignee, time)| format!("{} {}", assignee, worklog::format_duration(time)))
+                .collect();
+            println!("{}: {} ({})",r#"Time Logged"#.underline().bold().fg::<Aqua>(), worklog::format_duration(worklog::total(&task.worklog, now)), by_assignee.join(", "));
+            for work in task.running_timers() {
+                println!("    {} {} since {}", "▶".bold().fg::<LimeGreen>(), work.assignee, work.start.with_timezone(&Local).format("%Y-%m-%d %H:%M"));
+            }
+        }
+        println!("{}", ruler);
+    }
+}
+
+fn truncate(text: &str, width: usize) -> String{
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+
+    let mut truncated: String = text.chars().take(width.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Fits column widths into `width`, shrinking the free-text columns evenly
+/// (down to a few characters) when the table would not fit.
+fn fit_widths(columns: &[Column], mut widths: Vec<usize>, width: usize) -> Vec<usize>{
+    const GAP: usize = 2;
+    const MIN_SHRUNK: usize = 8;
+
+    let total = |widths: &[usize]| widths.iter().sum::<usize>() + GAP * widths.len().saturating_sub(1);
+
+    while total(&widths) > width {
+        let widest = columns.iter()
+            .zip(&widths)
+            .enumerate()
+            .filter(|(_, (column, w))| column.shrinkable() && **w > MIN_SHRUNK)
+            .max_by_key(|(_, (_, w))| **w)
+            .map(|(index, _)| index);
+
+        match widest {
+            Some(index) => widths[index] -= 1,
+            None        => break,
+        }
+    }
+
+    widths
+}
+
+/// Column widths that fit every task's cells into the terminal.
+fn table_widths(config: &Config, rows: &[Row], columns: &[Column]) -> Vec<usize>{
+    let natural: Vec<usize> = columns.iter()
+        .map(|column| {
+            rows.iter()
+                .map(|row| column.text(config, row).chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(column.header().len())
+        })
+        .collect();
+
+    fit_widths(columns, natural, terminal_width())
+}
+
+/// Prints one line per task with the chosen columns and widths.
+fn print_table(config: &Config, rows: &[Row], columns: &[Column], widths: &[usize]){
+    let header: Vec<String> = columns.iter()
+        .zip(widths)
+        .map(|(column, width)| format!("{:<width$}", truncate(column.header(), *width), width = width))
+        .collect();
+    println!("{}", header.join("  ").trim_end().underline().bold().fg::<Aqua>());
+
+    for row in rows {
+        let task = &row.task;
+        let cells: Vec<String> = columns.iter()
+            .zip(widths)
+            .map(|(column, width)| {
+                let text = column.text(config, row);
+                let cell = format!("{:<width$}", truncate(&text, *width), width = width);
+                // Colour after padding so the escape codes do not count towards the width.
+                match column {
+                    Column::Id          => cell.bold().fg::<White>().to_string(),
+                    Column::Priority    => cell.replace(&text, &colored_priority(&task.priority)),
+                    Column::Time if !task.running_timers().is_empty() => cell.bold().fg::<LimeGreen>().to_string(),
+                    // Truncated tags are left plain rather than colour half a tag.
+                    Column::Tags if text.chars().count() <= *width => {
+                        format!("{}{}", colored_tags(config, &task.tags), " ".repeat(width - text.chars().count()))
+                    }
+                    _                   => cell,
+                }
+            })
+            .collect();
+        println!("{}", cells.join("  ").trim_end());
+    }
+}
+
+/// Turns tasks into rows, nesting subtasks under their parents when `nest` is set.
+/// `all` holds every stored task, so progress, parents and blockers are known
+/// even for a filtered list.
+fn rows(config: &Config, all: &[Task], tasks: Vec<Task>, nest: bool) -> Vec<Row>{
+    let progress = tree::progress(all);
+    let by_uuid: HashMap<Uuid, &Task> = all.iter().map(|task| (task.uuid, task)).collect();
+    let dependents = deps::dependents(all);
+
+    let arranged = if nest {
+        tree::arrange(tasks)
+    } else {
+        tasks.into_iter().map(|task| (String::new(), task)).collect()
+    };
+
+    let describe = |uuid: Uuid| match by_uuid.get(&uuid) {
+        Some(task)  => format!("{} {}", config.key(task), task.title),
+        None        => uuid.to_string(),
+    };
+    let keys = |tasks: Vec<&Task>| -> Vec<String> {
+        tasks.into_iter()
+            .filter(|t| t.status.is_open())
+            .map(|t| config.key(t))
+            .collect()
+    };
+
+    arranged.into_iter()
+        .map(|(prefix, task)| Row{
+            prefix,
+            blockers:   keys(task.blocked_by.iter().filter_map(|uuid| by_uuid.get(uuid).copied()).collect()),
+            blocks:     keys(dependents.get(&task.uuid).cloned().unwrap_or_default()),
+            progress:   progress.get(&task.uuid).copied(),
+            parent:     task.parent.map(describe),
+            series:     task.series.map(describe),
+            task,
+        })
+        .collect()
+}
+
+/// Prints history entries one per line, with the task each is about when
+/// `with_task` is set.
+pub fn print_history(config: &Config, entries: &[Entry], with_task: bool){
+    if entries.is_empty() {
+        println!("No changes recorded");
+    }
+
+    let width = entries.iter().map(|entry| entry.actor.chars().count()).max().unwrap_or(0);
+    let value = |text: &str| match text.is_empty() {
+        true    => "(none)".to_string(),
+        false   => text.to_string(),
+    };
+    for entry in entries {
+        let task = match with_task {
+            true    => format!("{} {}  ", config.project_key(&entry.project, entry.id).bold().fg::<White>(), entry.title),
+            false   => String::new(),
+        };
+        let change = match entry.field.as_str() {
+            "created"   => "created".fg::<LimeGreen>().to_string(),
+            "deleted"   => "deleted".fg::<Red>().to_string(),
+            "archived"  => "archived".dimmed().to_string(),
+            "restored"  => "restored".fg::<LimeGreen>().to_string(),
+            "unarchived"=> "unarchived".fg::<LimeGreen>().to_string(),
+            field       => format!("{}: {} -> {}", field.fg::<Aqua>(), value(&entry.old), value(&entry.new)),
+        };
+        println!("{}  {:<width$}  {}{}", entry.at.with_timezone(&Local).format("%Y-%m-%d %H:%M"), entry.actor, task, change, width = width);
+    }
+}
+
+/// Prints the tasks in the trash with when they were removed and, if the
+/// trash expires, when they will be purged.
+pub fn print_trash(config: &Config, tasks: &[Task]){
+    if tasks.is_empty() {
+        println!("The trash is empty");
+    }
+
+    let width = tasks.iter().map(|task| config.key(task).chars().count()).max().unwrap_or(0);
+    for task in tasks {
+        let Some(deleted_at) = task.deleted_at else {
+            continue;
+        };
+        let expires = match config.trash.retention_days {
+            0       => String::new(),
+            days    => format!(", purged on {}", (deleted_at + chrono::Duration::days(days.into())).with_timezone(&Local).format("%Y-%m-%d")),
+        };
+        println!("{:<width$}  {}  {}", config.key(task).bold().fg::<White>(), task.title, format!("removed {}{}", deleted_at.with_timezone(&Local).format("%Y-%m-%d %H:%M"), expires).dimmed(), width = width);
+    }
+}
+
+/// Prints what an operation changed under a heading such as `Undid`.
+pub fn print_operation(config: &Config, operation: &Operation, heading: &str){
+    if let Some(first) = operation.events.first() {
+        println!("{} the change made by {} on {}:", heading, first.actor, first.at.with_timezone(&Local).format("%Y-%m-%d %H:%M"));
+    }
+    print_history(config, &operation.entries(), true);
+}
+
+/// Prints a task's comment thread, oldest first.
+pub fn print_comments(task: &Task){
+    if task.comments.is_empty() {
+        println!("No comments yet");
+    }
+    for comment in &task.comments {
+        println!("{} {}", comment.author.bold().fg::<Aqua>(), comment.at.with_timezone(&Local).format("%Y-%m-%d %H:%M"));
+        for line in comment.text.lines() {
+            println!("    {}", line);
+        }
+    }
+}
+
+/// Prints a single task with every field, followed by its comment thread.
+pub fn print_task(config: &Config, all: &[Task], task: Task){
+    let rows = rows(config, all, vec![task], false);
+    print_blocks(config, &rows);
+    println!("{}", r#"Comments"#.underline().bold().fg::<Aqua>());
+    print_comments(&rows[0].task);
+}
+
+/// Prints tasks sorted, grouped and laid out according to `options`. `all`
+/// holds every stored task, for the subtasks and blockers of those listed.
+pub fn print_list(config: &Config, all: &[Task], mut tasks: Vec<Task>, options: &ListOptions){
+    sort(&mut tasks, &options.sort, options.reverse);
+
+    // Every group shares the same widths so the columns line up across groups.
+    let widths = table_widths(config, &rows(config, all, tasks.clone(), options.tree), &options.columns);
+    let print = |tasks: &[Task]| {
+        let rows = rows(config, all, tasks.to_vec(), options.tree);
+        if options.table {
+            print_table(config, &rows, &options.columns, &widths);
+        } else {
+            print_blocks(config, &rows);
+        }
+    };
+
+    match options.group {
+        Some(by) => {
+            for (name, members) in group(tasks, by) {
+                println!();
+                println!("{} ({})", name.bold().underline(), members.len());
+                print(&members);
+            }
+        }
+        None => print(&tasks),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: i32, priority: Priority, due: &str, status: Status, assignees: &[&str]) -> Task{
+        let mut task = Task::new(priority, format!("Task {}", id), String::new(), due.parse().unwrap(), assignees.iter().map(|a| a.to_string()).collect());
+        task.id = id;
+        task.status = status;
+        task
+    }
+
+    fn tasks() -> Vec<Task>{
+        vec![
+            task(1, Priority::Low, "2026-10-20", Status::Completed, &["alice"]),
+            task(2, Priority::Critical, "2026-10-22", Status::NotCompleted, &[]),
+            task(3, Priority::High, "2026-10-20", Status::InProgress, &["bob", "alice"]),
+            task(4, Priority::High, "2026-10-19", Status::NotCompleted, &["bob"]),
+        ]
+    }
+
+    fn ids(tasks: &[Task]) -> Vec<i32>{
+        tasks.iter().map(|t| t.id).collect()
+    }
+
+    #[test]
+    fn later_sort_keys_break_ties(){
+        let mut sorted = tasks();
+        sort(&mut sorted, &[SortKey::Priority, SortKey::Due], false);
+        assert_eq!(ids(&sorted), [2, 4, 3, 1]);
+
+        sort(&mut sorted, &[SortKey::Status, SortKey::Id], false);
+        assert_eq!(ids(&sorted), [2, 4, 3, 1]);
+
+        sort(&mut sorted, &[SortKey::Due], true);
+        assert_eq!(ids(&sorted), [2, 3, 1, 4]);
+    }
+
+    #[test]
+    fn tasks_appear_in_every_group_they_belong_to(){
+        let groups = group(tasks(), GroupBy::Assignee);
+        let names: Vec<&str> = groups.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["(unassigned)", "alice", "bob"]);
+        assert_eq!(ids(&groups[1].1), [1, 3]);
+        assert_eq!(ids(&groups[2].1), [3, 4]);
+
+        let groups = group(tasks(), GroupBy::Priority);
+        assert_eq!(groups[0].0, "Critical");
+        assert_eq!(ids(&groups[1].1), [3, 4]);
+    }
+
+    #[test]
+    fn only_free_text_columns_shrink(){
+        let columns = [Column::Id, Column::Title, Column::Description];
+        assert_eq!(fit_widths(&columns, vec![4, 30, 20], 100), [4, 30, 20]);
+        assert_eq!(fit_widths(&columns, vec![4, 30, 20], 40), [4, 16, 16]);
+        assert_eq!(fit_widths(&columns, vec![4, 30, 20], 10), [4, 8, 8]);
+        assert_eq!(truncate("Deploy staging", 7), "Deploy…");
+    }
+
+    #[test]
+    fn rows_show_open_blockers_and_blocked_tasks(){
+        let mut all = tasks();
+        all[1].blocked_by = vec![all[0].uuid, all[2].uuid];
+        all[3].blocked_by = vec![all[2].uuid];
+
+        let rows = rows(&Config::default(), &all, all.clone(), false);
+        assert!(rows[1].blockers == ["3"]);
+        assert!(rows[2].blocks == ["2", "4"]);
+        assert!(rows[3].blocks.is_empty());
+    }
+}
@@ -0,0 +1,128 @@
+use chrono::{
+    DateTime,
+    Duration,
+    Utc
+};
+use serde::{
+    Serialize,
+    Deserialize
+};
+use std::collections::BTreeMap;
+
+use crate::error::{
+    Error,
+    Result
+};
+
+/// Time someone worked on a task. Entries without an end are running timers.
+#[derive(Debug,Clone,Serialize,Deserialize)]
+pub struct Work{
+    pub assignee:   String,
+    pub start:      DateTime<Utc>,
+    pub end:        Option<DateTime<Utc>>,
+}
+
+impl Work{
+    pub fn is_running(&self) -> bool{
+        self.end.is_none()
+    }
+
+    /// How long the work lasted; running timers count until `now`.
+    pub fn duration(&self, now: DateTime<Utc>) -> Duration{
+        (self.end.unwrap_or(now) - self.start).max(Duration::zero())
+    }
+}
+
+/// The time logged in `entries`, up to `now` for running timers.
+pub fn total(entries: &[Work], now: DateTime<Utc>) -> Duration{
+    entries.iter().map(|work| work.duration(now)).sum()
+}
+
+/// The time logged in `entries` by each assignee.
+pub fn by_assignee<'a>(entries: impl IntoIterator<Item = &'a Work>, now: DateTime<Utc>) -> BTreeMap<String, Duration>{
+    let mut totals: BTreeMap<String, Duration> = BTreeMap::new();
+    for work in entries {
+        *totals.entry(work.assignee.clone()).or_insert_with(Duration::zero) += work.duration(now);
+    }
+    totals
+}
+
+/// Parses a duration such as `1h30m`, `45m`, `1.5h` or `90` (minutes).
+pub fn parse_duration(input: &str) -> Result<Duration>{
+    let invalid = || Error::Invalid(format!("Invalid duration: {}, expected e.g. 1h30m, 45m, 1.5h or 90 (minutes)", input));
+    let text: String = input.trim().to_lowercase().split_whitespace().collect();
+
+    if let Ok(minutes) = text.parse::<f64>() {
+        return minutes_to_duration(minutes).ok_or_else(invalid);
+    }
+
+    let mut minutes = 0.0;
+    let mut rest = text.as_str();
+    while !rest.is_empty() {
+        let unit_start = rest.find(|c: char| c.is_ascii_alphabetic()).ok_or_else(invalid)?;
+        let (number, after) = rest.split_at(unit_start);
+        let unit_end = after.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(after.len());
+        let (unit, after) = after.split_at(unit_end);
+
+        let number: f64 = number.parse().map_err(|_| invalid())?;
+        minutes += match unit {
+            "h" | "hr" | "hrs" | "hour" | "hours"           => number * 60.0,
+            "m" | "min" | "mins" | "minute" | "minutes"     => number,
+            _                                               => return Err(invalid()),
+        };
+        rest = after;
+    }
+
+    minutes_to_duration(minutes).filter(|duration| *duration > Duration::zero()).ok_or_else(invalid)
+}
+
+fn minutes_to_duration(minutes: f64) -> Option<Duration>{
+    match minutes.is_finite() && minutes > 0.0 && minutes < 1e9 {
+        true    => Some(Duration::seconds((minutes * 60.0).round() as i64)),
+        false   => None,
+    }
+}
+
+/// Formats a duration as hours and minutes, e.g. `2h 05m` or `45m`.
+pub fn format_duration(duration: Duration) -> String{
+    let minutes = duration.num_minutes();
+    match minutes / 60 {
+        0       => format!("{}m", minutes),
+        hours   => format!("{}h {:02}m", hours, minutes % 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(minute: i64) -> DateTime<Utc>{
+        "2026-10-18T09:00:00Z".parse::<DateTime<Utc>>().unwrap() + Duration::minutes(minute)
+    }
+
+    fn work(assignee: &str, start: i64, end: Option<i64>) -> Work{
+        Work{ assignee: assignee.to_string(), start: at(start), end: end.map(at) }
+    }
+
+    #[test]
+    fn durations(){
+        for (input, minutes) in [("90", 90), ("1h30m", 90), ("1.5h", 90), ("45 min", 45), ("2 hours 5 minutes", 125)] {
+            assert_eq!(parse_duration(input).unwrap(), Duration::minutes(minutes), "{}", input);
+        }
+        for input in ["", "0", "-5", "h", "3d", "1h30", "nan"] {
+            assert!(parse_duration(input).is_err(), "{} was accepted", input);
+        }
+        assert_eq!(format_duration(Duration::minutes(125)), "2h 05m");
+        assert_eq!(format_duration(Duration::minutes(45)), "45m");
+    }
+
+    #[test]
+    fn running_timers_count_until_now(){
+        let entries = [work("alice", 0, Some(30)), work("bob", 10, Some(25)), work("alice", 60, None)];
+        assert_eq!(total(&entries, at(90)), Duration::minutes(75));
+
+        let totals = by_assignee(&entries, at(90));
+        assert_eq!(totals["alice"], Duration::minutes(60));
+        assert_eq!(totals["bob"], Duration::minutes(15));
+    }
+}